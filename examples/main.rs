@@ -1,4 +1,4 @@
-use pkhex_rs::{utils::SliceUtils, save::gen3::{gen3_save::{SaveGen3, Gen3Game}, gen3_utils::parse_trainer_data_from_byte_array}};
+use pkhex_rs::save::gen3::gen3_save::SaveGen3;
 
 fn main() {
 
@@ -7,23 +7,10 @@ fn main() {
     Err(e) => panic!("{}", e)
   };
 
-  // let save_a_bytes = file_bytes.get_offset(0x0, 57344);
-  let save_b_bytes = file_bytes.get_offset(0x00E000, 57344);
-
-  let mut save = SaveGen3 {
-      trainer_section: None,
-      game_ver: Gen3Game::RubySapphire,
+  let save = match SaveGen3::from_bytes(&file_bytes) {
+    Ok(save) => save,
+    Err(e) => panic!("The file is not a valid Gen 3 save: {}", e)
   };
 
-  for i in 0..14 {
-      let current_section = save_b_bytes.get_offset(4096*i, 4096);
-      let section_id = current_section.get_u16_le_offset(0x0FF4).unwrap();
-
-      match section_id {
-          0 => save.trainer_section = Some(parse_trainer_data_from_byte_array(current_section, &mut save).unwrap()),
-          _ => println!("The section ID is {}", section_id)
-      };
-  }
-
   println!("{:#?}", save);
 }
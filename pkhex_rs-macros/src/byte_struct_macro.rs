@@ -0,0 +1,293 @@
+use proc_macro::{TokenStream, Span};
+use syn::{Ident, Type, Expr, DeriveInput, Data, Fields, Visibility, parse::Parse, ext::IdentExt};
+use quote::{quote, format_ident};
+
+use crate::kw;
+use crate::field_type::{self, BitRange, FieldType, XorKey};
+use crate::layout::{self, FieldLayout, LayoutOptions};
+
+pub(crate) struct ByteField {
+    pub var_name: Ident,
+    pub var_type: Type,
+    /// The type an enum field is stored as
+    pub raw: Option<Type>,
+    pub xor: Option<XorKey>,
+    pub offset: Expr,
+    pub bits: Option<BitRange>,
+    pub endianess: Ident,
+    pub union: bool
+}
+
+pub(crate) struct ByteStruct {
+    pub name: Ident,
+    pub visibility: Visibility,
+    pub options: LayoutOptions,
+    pub fields: Vec<ByteField>
+}
+
+fn parse_endianess(input: syn::parse::ParseStream) -> syn::Result<Ident> {
+    let endian_lookahead = input.lookahead1();
+    if endian_lookahead.peek(kw::be) || endian_lookahead.peek(kw::le) {
+        input.call(Ident::parse_any)
+    } else {
+        Err(endian_lookahead.error())
+    }
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<ByteField> {
+    let var_name = field.ident.clone().ok_or_else(|| syn::Error::new_spanned(field, "Expected a named field"))?;
+
+    let mut offset = None;
+    let mut bits = None;
+    let mut endianess = None;
+    for attr in &field.attrs {
+        if attr.path().is_ident("offset") {
+            offset = Some(attr.parse_args::<Expr>()?);
+        } else if attr.path().is_ident("bits") {
+            bits = Some(BitRange::from_expr(&attr.parse_args::<Expr>()?)?);
+        } else if attr.path().is_ident("endian") {
+            endianess = Some(attr.parse_args_with(parse_endianess)?);
+        }
+    }
+
+    let offset = offset.ok_or_else(|| syn::Error::new_spanned(&var_name, "Expected an `#[offset(...)]` attribute"))?;
+
+    Ok(ByteField {
+        var_name,
+        var_type: field.ty.clone(),
+        raw: field_type::raw_type(&field.attrs)?,
+        xor: XorKey::from_attributes(&field.attrs)?,
+        offset,
+        bits,
+        endianess: endianess.unwrap_or_else(|| Ident::new("le", Span::call_site().into())),
+        union: layout::is_union(&field.attrs)
+    })
+}
+
+impl Parse for ByteStruct {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let derive_input: DeriveInput = input.parse()?;
+
+        let fields = match &derive_input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) => &fields.named,
+                _ => return Err(syn::Error::new_spanned(&derive_input.ident, "ByteStruct needs a struct with named fields"))
+            },
+            _ => return Err(syn::Error::new_spanned(&derive_input.ident, "ByteStruct can only be derived for structs"))
+        };
+
+        // Every field is parsed so all their errors are reported at once
+        let mut errors: Option<syn::Error> = None;
+        let mut byte_fields = Vec::new();
+        for field in fields {
+            match parse_field(field) {
+                Ok(byte_field) => byte_fields.push(byte_field),
+                Err(error) => match errors.as_mut() {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error)
+                }
+            }
+        }
+
+        if let Some(errors) = errors {
+            return Err(errors);
+        }
+
+        Ok(ByteStruct {
+            name: derive_input.ident.clone(),
+            visibility: derive_input.vis.clone(),
+            options: LayoutOptions::from_attributes(&derive_input.attrs)?,
+            fields: byte_fields
+        })
+    }
+}
+
+pub(crate) fn expand_byte_struct(macro_input: ByteStruct) -> TokenStream {
+    let ByteStruct {
+        name,
+        visibility,
+        options,
+        fields,
+    } = macro_input;
+
+    let view_name = format_ident!("{}View", name);
+    let view_mut_name = format_ident!("{}ViewMut", name);
+
+    let mut getters = Vec::new();
+    let mut mut_getters = Vec::new();
+    let mut setters = Vec::new();
+
+    let mut errors: Option<syn::Error> = None;
+    let mut kinds = Vec::new();
+    for f in &fields {
+        let kind = FieldType::from_field(f.raw.as_ref().unwrap_or(&f.var_type), f.bits.as_ref())
+            .and_then(|kind| match &f.xor {
+                Some(xor) => xor.check(&kind, f.bits.as_ref(), f.raw.as_ref()).map(|_| kind),
+                None => Ok(kind)
+            });
+
+        match kind {
+            Ok(kind) => kinds.push(kind),
+            Err(error) => match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error)
+            }
+        }
+    }
+
+    if let Some(errors) = errors {
+        return errors.to_compile_error().into();
+    }
+
+    let mut layouts = Vec::new();
+
+    for (f, kind) in fields.iter().zip(&kinds) {
+        layouts.push(FieldLayout {
+            var_name: &f.var_name,
+            offset: &f.offset,
+            var_type: f.raw.as_ref().unwrap_or(&f.var_type),
+            size: f.bits.map(|bits| bits.container().1).or(kind.size()),
+            bits: f.bits,
+            big_endian: f.endianess == "be",
+            union: f.union
+        });
+
+        let var_name = &f.var_name;
+        let setter_name = format_ident!("set_{}", var_name);
+        let getter = kind.getter(&f.endianess, quote!(self.data), &f.offset, f.bits.as_ref());
+        let setter = kind.setter(&f.endianess, quote!(&mut *self.data), &f.offset, quote!(value), f.bits.as_ref());
+
+        let field_type = &f.var_type;
+        let offset = &f.offset;
+        let location = match &f.bits {
+            Some(bits) => format!("`{}`, bits {}..{}", quote!( #offset ), bits.start, bits.end),
+            None => format!("`{}`", quote!( #offset ))
+        };
+        let location = match &f.xor {
+            Some(xor) => format!("{}, XORed with `{}`", location, xor.name),
+            None => location
+        };
+        let get_doc = format!("Reads `{}` at {}", var_name, location);
+        let set_doc = format!("Writes `{}` at {}", var_name, location);
+
+        let (getter, setter) = match &f.raw {
+            Some(raw_type) => {
+                // Enum fields also get raw accessors for the values the enum doesn't cover
+                let raw_name = format_ident!("{}_raw", var_name);
+                let raw_setter_name = format_ident!("set_{}_raw", var_name);
+                let raw_get_doc = format!("Reads `{}` at {} as a raw `{}`", var_name, location, quote!( #raw_type ));
+                let raw_set_doc = format!("Writes `{}` at {} from a raw `{}`", var_name, location, quote!( #raw_type ));
+
+                getters.push(quote! {
+                    #[doc = #raw_get_doc]
+                    #visibility fn #raw_name (&self) -> Result<#raw_type, Error> {
+                        #getter
+                    }
+                });
+
+                mut_getters.push(quote! {
+                    #[doc = #raw_get_doc]
+                    #visibility fn #raw_name (&self) -> Result<#raw_type, Error> {
+                        self.as_view(). #raw_name ()
+                    }
+                });
+
+                setters.push(quote! {
+                    #[doc = #raw_set_doc]
+                    #visibility fn #raw_setter_name (&mut self, value: #raw_type) -> Result<(), Error> {
+                        #setter
+                    }
+                });
+
+                (field_type::enum_getter(field_type, raw_type, &getter), field_type::enum_setter(raw_type, &setter))
+            },
+            None => (getter, setter)
+        };
+
+        let (key, key_name, getter, setter) = match &f.xor {
+            Some(xor) => (Some(xor.param(field_type)), Some(&xor.name), xor.getter(field_type, &getter), xor.setter(field_type, &setter)),
+            None => (None, None, getter, setter)
+        };
+
+        getters.push(quote! {
+            #[doc = #get_doc]
+            #visibility fn #var_name (&self, #key) -> Result<#field_type, Error> {
+                #getter
+            }
+        });
+
+        mut_getters.push(quote! {
+            #[doc = #get_doc]
+            #visibility fn #var_name (&self, #key) -> Result<#field_type, Error> {
+                self.as_view(). #var_name (#key_name)
+            }
+        });
+
+        setters.push(quote! {
+            #[doc = #set_doc]
+            #visibility fn #setter_name (&mut self, #key value: #field_type) -> Result<(), Error> {
+                #setter
+            }
+        });
+    }
+
+    let assertions = match layout::check_layout(&options, &layouts) {
+        Ok(assertions) => assertions,
+        Err(error) => return error.to_compile_error().into()
+    };
+
+    let view_doc = format!("Reads the fields of [`{}`] straight from its bytes", name);
+    let view_mut_doc = format!("Reads and writes the fields of [`{}`] straight into its bytes", name);
+
+    quote! {
+        #assertions
+
+        impl #name {
+            #visibility fn view(data: &[u8]) -> #view_name<'_> {
+                #view_name::new(data)
+            }
+
+            #visibility fn view_mut(data: &mut [u8]) -> #view_mut_name<'_> {
+                #view_mut_name::new(data)
+            }
+        }
+
+        #[doc = #view_doc]
+        #[derive(Debug, Clone, Copy)]
+        #visibility struct #view_name<'a> {
+            data: &'a [u8]
+        }
+
+        impl<'a> #view_name<'a> {
+            #visibility fn new(data: &'a [u8]) -> Self {
+                #view_name { data }
+            }
+
+            #visibility fn bytes(&self) -> &'a [u8] {
+                self.data
+            }
+
+            #(#getters)*
+        }
+
+        #[doc = #view_mut_doc]
+        #[derive(Debug)]
+        #visibility struct #view_mut_name<'a> {
+            data: &'a mut [u8]
+        }
+
+        impl<'a> #view_mut_name<'a> {
+            #visibility fn new(data: &'a mut [u8]) -> Self {
+                #view_mut_name { data }
+            }
+
+            #visibility fn as_view(&self) -> #view_name<'_> {
+                #view_name::new(self.data)
+            }
+
+            #(#mut_getters)*
+
+            #(#setters)*
+        }
+    }.into()
+}
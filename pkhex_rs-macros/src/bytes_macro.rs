@@ -1,31 +1,46 @@
 use proc_macro::{TokenStream, Span};
-use syn::{Ident, Type, parse::Parse, Token, Expr, punctuated::Punctuated, ext::IdentExt};
+use syn::{Ident, Type, parse::Parse, Token, Expr, Attribute, punctuated::Punctuated, ext::IdentExt};
 use quote::{quote, format_ident};
 
 use crate::kw;
+use crate::field_type::{self, BitRange, FieldType, XorKey};
+use crate::layout::{self, FieldLayout, LayoutOptions};
 
 pub(crate)struct BytesGetSet {
     pub var_name: Ident,
     pub var_type: Type,
+    /// The type an enum field is stored as
+    pub raw: Option<Type>,
+    pub xor: Option<XorKey>,
     pub offset: Expr,
-    pub endianess: Ident
+    pub bits: Option<BitRange>,
+    pub endianess: Ident,
+    pub union: bool
 }
 
 impl Parse for BytesGetSet {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let union = layout::is_union(&attrs);
+        let raw = field_type::raw_type(&attrs)?;
+        let xor = XorKey::from_attributes(&attrs)?;
         let var_name: Ident = input.parse().map_err(|_| input.error("Expected field name"))?;
         input.parse::<Token![:]>().map_err(|_| input.error("Expected `:`"))?;
 
         let var_type: Type = input.parse().map_err(|_| input.error("Expected field type"))?;
         input.parse::<Token![@]>().map_err(|_| input.error("Expected `@`"))?;
-        let offset: Expr  = input.parse()?;
+        let (offset, bits) = BitRange::split_offset(input.parse()?)?;
 
         if !input.peek(Token![#]) {
             return Ok(BytesGetSet {
                 var_name,
                 var_type,
+                raw,
+                xor,
                 offset,
-                endianess: Ident::new("le", Span::call_site().into())
+                bits,
+                endianess: Ident::new("le", Span::call_site().into()),
+                union
             })
         }
 
@@ -41,21 +56,28 @@ impl Parse for BytesGetSet {
         Ok(BytesGetSet {
             var_name,
             var_type,
+            raw,
+            xor,
             offset,
-            endianess
+            bits,
+            endianess,
+            union
         })
     }
 }
 
 pub(crate) struct BytesGetSetCollection {
+    pub options: LayoutOptions,
     pub fields: Punctuated<BytesGetSet, Token![,]>
 }
 
 impl Parse for BytesGetSetCollection {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let options = LayoutOptions::from_attributes(&input.call(Attribute::parse_inner)?)?;
         let fields = input.parse_terminated(BytesGetSet::parse, Token![,])?;
         Ok(
             BytesGetSetCollection { 
+                options,
                 fields
             }
         )
@@ -63,34 +85,102 @@ impl Parse for BytesGetSetCollection {
 }
 
 pub(crate) fn expand_byte_get_set(macro_input: BytesGetSetCollection) -> TokenStream {
-    let mapped_fields = macro_input.fields.iter().map(|f| {
+    let mut errors: Option<syn::Error> = None;
+    let mut field_types = Vec::new();
+    for f in &macro_input.fields {
+        let field_type = FieldType::from_field(f.raw.as_ref().unwrap_or(&f.var_type), f.bits.as_ref())
+            .and_then(|field_type| match &f.xor {
+                Some(xor) => xor.check(&field_type, f.bits.as_ref(), f.raw.as_ref()).map(|_| field_type),
+                None => Ok(field_type)
+            });
+
+        match field_type {
+            Ok(field_type) => field_types.push(field_type),
+            Err(error) => match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error)
+            }
+        }
+    }
+
+    if let Some(errors) = errors {
+        return errors.to_compile_error().into();
+    }
+
+    let layouts: Vec<FieldLayout> = macro_input.fields.iter().zip(&field_types).map(|(f, field_type)| FieldLayout {
+        var_name: &f.var_name,
+        offset: &f.offset,
+        var_type: f.raw.as_ref().unwrap_or(&f.var_type),
+        size: f.bits.map(|bits| bits.container().1).or(field_type.size()),
+        bits: f.bits,
+        big_endian: f.endianess == "be",
+        union: f.union
+    }).collect();
+
+    let assertions = match layout::check_layout(&macro_input.options, &layouts) {
+        Ok(assertions) => assertions,
+        Err(error) => return error.to_compile_error().into()
+    };
+
+    let mapped_fields = macro_input.fields.iter().zip(&field_types).enumerate().map(|(i, (f, kind))| {
         let getter_name = format_ident!("get_{}_from_bytes", &f.var_name);
         let setter_name = format_ident!("set_{}_from_bytes", &f.var_name);
 
-        let type_string = match &f.var_type {
-            Type::Path(verbatim) => verbatim.path.get_ident(),
-            _ => panic!("No valid type was found")
+        let field_type = &f.var_type;
+        let getter = kind.getter(&f.endianess, quote!(data), &f.offset, f.bits.as_ref());
+        let setter = kind.setter(&f.endianess, quote!(data), &f.offset, quote!(value), f.bits.as_ref());
+        // The layout only needs to be checked once
+        let assertions = if i == 0 { Some(&assertions) } else { None };
+        let array_accessors = macro_input.options.size.as_ref().map(|size| array_accessors(f, kind, size));
+
+        let raw_type = match &f.raw {
+            Some(raw_type) => raw_type,
+            None => {
+                let (key, getter, setter) = match &f.xor {
+                    Some(xor) => (Some(xor.param(field_type)), xor.getter(field_type, &getter), xor.setter(field_type, &setter)),
+                    None => (None, getter, setter)
+                };
+
+                return quote! {
+                    pub fn #getter_name (data: &[u8], #key) -> Result<#field_type, Error> {
+                        #assertions
+                        #getter
+                    }
+
+                    pub fn #setter_name (data: &mut [u8], #key value: #field_type) -> Result<(), Error> {
+                        #setter
+                    }
+
+                    #array_accessors
+                }
+            }
         };
 
-        if type_string.is_none() || (type_string.unwrap() != "u16" && type_string.unwrap() != "u32") {
-            panic!("The type must be u16 or u32");
-        }
-
-        let get_fn = format_ident!("get_{}_{}_offset", type_string.unwrap(), &f.endianess);
-        let set_fn = format_ident!("to_{}_bytes", &f.endianess);
-        
-        let field_type = &f.var_type;
-        let offset = &f.offset;
+        // Enum fields also get raw accessors for the values the enum doesn't cover
+        let raw_getter_name = format_ident!("get_{}_raw_from_bytes", &f.var_name);
+        let raw_setter_name = format_ident!("set_{}_raw_from_bytes", &f.var_name);
+        let enum_getter = field_type::enum_getter(field_type, raw_type, &getter);
+        let enum_setter = field_type::enum_setter(raw_type, &setter);
 
         quote! {
-            pub fn #getter_name (data: &[u8]) -> #field_type {
-                SliceUtils:: #get_fn (data, #offset).unwrap()
+            pub fn #getter_name (data: &[u8]) -> Result<#field_type, Error> {
+                #assertions
+                #enum_getter
+            }
+
+            pub fn #setter_name (data: &mut [u8], value: #field_type) -> Result<(), Error> {
+                #enum_setter
+            }
+
+            pub fn #raw_getter_name (data: &[u8]) -> Result<#raw_type, Error> {
+                #getter
             }
 
-            pub fn #setter_name (data: &mut [u8], value: #field_type ) {
-                let value_bytes = #field_type :: #set_fn (value);
-                data.write_into(&value_bytes, #offset);
+            pub fn #raw_setter_name (data: &mut [u8], value: #raw_type) -> Result<(), Error> {
+                #setter
             }
+
+            #array_accessors
         }
     });
 
@@ -98,3 +188,66 @@ pub(crate) fn expand_byte_get_set(macro_input: BytesGetSetCollection) -> TokenSt
         #(#mapped_fields)*
     }.into()
 }
+
+/// Accessors of a field in an array of at least the declared size, which the layout
+/// checks already proved to hold the field, so only the enum getters can fail
+fn array_accessors(f: &BytesGetSet, kind: &FieldType, size: &Expr) -> proc_macro2::TokenStream {
+    let getter_name = format_ident!("get_{}_from_array", &f.var_name);
+    let setter_name = format_ident!("set_{}_from_array", &f.var_name);
+
+    let field_type = &f.var_type;
+    let getter = kind.array_getter(&f.endianess, quote!(data), &f.offset, f.bits.as_ref());
+    let setter = kind.array_setter(&f.endianess, quote!(data), &f.offset, quote!(value), f.bits.as_ref());
+    let size_check = quote! {
+        const { assert!(N >= (#size), "The array is smaller than the declared size") };
+    };
+
+    let raw_type = match &f.raw {
+        Some(raw_type) => raw_type,
+        None => {
+            let (key, getter, setter) = match &f.xor {
+                Some(xor) => (Some(xor.param(field_type)), xor.array_getter(field_type, &getter), xor.setter(field_type, &setter)),
+                None => (None, getter, setter)
+            };
+
+            return quote! {
+                pub fn #getter_name <const N: usize>(data: &[u8; N], #key) -> #field_type {
+                    #size_check
+                    #getter
+                }
+
+                pub fn #setter_name <const N: usize>(data: &mut [u8; N], #key value: #field_type) {
+                    #size_check
+                    #setter
+                }
+            }
+        }
+    };
+
+    let raw_getter_name = format_ident!("get_{}_raw_from_array", &f.var_name);
+    let raw_setter_name = format_ident!("set_{}_raw_from_array", &f.var_name);
+    let enum_getter = field_type::enum_array_getter(field_type, raw_type, &getter);
+    let enum_setter = field_type::enum_setter(raw_type, &setter);
+
+    quote! {
+        pub fn #getter_name <const N: usize>(data: &[u8; N]) -> Result<#field_type, Error> {
+            #size_check
+            #enum_getter
+        }
+
+        pub fn #setter_name <const N: usize>(data: &mut [u8; N], value: #field_type) {
+            #size_check
+            #enum_setter
+        }
+
+        pub fn #raw_getter_name <const N: usize>(data: &[u8; N]) -> #raw_type {
+            #size_check
+            #getter
+        }
+
+        pub fn #raw_setter_name <const N: usize>(data: &mut [u8; N], value: #raw_type) {
+            #size_check
+            #setter
+        }
+    }
+}
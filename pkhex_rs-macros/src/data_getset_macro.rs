@@ -19,6 +19,9 @@ impl Parse for DataGetSet {
         input.parse::<kw::get>()?;
         input.parse::<Token![=>]>()?;
         let get_block: Expr  = input.parse()?;
+        if !matches!(get_block, Expr::Block(_)) {
+            return Err(syn::Error::new_spanned(&get_block, "The expression is not a block, wrap it in `{ ... }`"));
+        }
 
         Ok(DataGetSet {
             var_name,
@@ -35,43 +38,41 @@ pub(crate) fn expand_data_get_set(macro_input: DataGetSet) -> TokenStream{
         get_block,
     } = macro_input;
 
-    if let Expr::Block(ref _get_block) = get_block {
-    } else {
-        panic!("The expression is not a block");
-    }
-
     let fn_name = format_ident!("get_{}_from_bytes", var_name);
     let var_type_string = quote!( #var_type ).to_string();
     let get_block_string = quote!( #get_block ).to_string();
     
     let doc_part = if let Type::Array(_) = var_type {
         format!(r"# let data = <[u8; 32]>::default();
-let {var_name} = {fn_name}(&data);")
+let {var_name} = {fn_name}(&data)?;
+# Ok::<(), Error>(())")
     } else {
         format!(r"# let data = {var_type_string}::to_le_bytes(0x2A);
-let number = {fn_name}(&data);
-# assert_eq!(number, 42);")
+let number = {fn_name}(&data)?;
+# assert_eq!(number, 42);
+# Ok::<(), Error>(())")
     };
 
     let docs = format!(r"# Examples
 ```
 # use pkhex_rs_macros::data_get_set_proc;
-# use std::array::TryFromSliceError;
+# #[derive(Debug)]
+# pub struct Error;
 # pub trait SliceUtils {{
 # fn write_into(&mut self, data: &[u8], offset: usize);
-# fn get_offset(&self, offset: usize, byte_quantity: usize) -> &[u8];
-# fn get_u16_le(&self) -> Result<u16, TryFromSliceError>;
-# fn get_u32_le(&self) -> Result<u32, TryFromSliceError>;
-# fn get_u16_le_offset(&self, offset: usize) -> Result<u16, TryFromSliceError>;
-# fn get_u32_le_offset(&self, offset: usize) -> Result<u32, TryFromSliceError>;
+# fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error>;
+# fn get_u16_le(&self) -> Result<u16, Error>;
+# fn get_u32_le(&self) -> Result<u32, Error>;
+# fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error>;
+# fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error>;
 # }}
 # impl SliceUtils for [u8] {{
-# fn write_into(&mut self, data: &[u8], offset: usize) {{ self[offset..(data.len())].copy_from_slice(data);}}
-# fn get_offset(&self, offset: usize, byte_quantity: usize) -> &[u8] {{ &self[offset..offset + byte_quantity] }}
-# fn get_u16_le(&self) -> Result<u16, TryFromSliceError> {{ Ok(u16::from_le_bytes(self[..2].try_into()?)) }}
-# fn get_u32_le(&self) -> Result<u32, TryFromSliceError> {{ Ok(u32::from_le_bytes(self[..4].try_into()?)) }}
-# fn get_u16_le_offset(&self, offset: usize) -> Result<u16, TryFromSliceError> {{ self.get_offset(offset, 2).get_u16_le() }}
-# fn get_u32_le_offset(&self, offset: usize) -> Result<u32, TryFromSliceError> {{ self.get_offset(offset, 4).get_u32_le() }}
+# fn write_into(&mut self, data: &[u8], offset: usize) {{ self[offset..offset + data.len()].copy_from_slice(data);}}
+# fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error> {{ self.get(offset..offset + byte_quantity).ok_or(Error) }}
+# fn get_u16_le(&self) -> Result<u16, Error> {{ Ok(u16::from_le_bytes(self.get_offset(0, 2)?.try_into().map_err(|_| Error)?)) }}
+# fn get_u32_le(&self) -> Result<u32, Error> {{ Ok(u32::from_le_bytes(self.get_offset(0, 4)?.try_into().map_err(|_| Error)?)) }}
+# fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error> {{ self.get_offset(offset, 2)?.get_u16_le() }}
+# fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error> {{ self.get_offset(offset, 4)?.get_u32_le() }}
 # }}
 # data_get_set_proc! {{ {var_name}: {var_type_string};
 #   get => {get_block_string}
@@ -81,6 +82,6 @@ let number = {fn_name}(&data);
 
     quote! {
         #[doc = #docs]
-        pub fn #fn_name (data: &[u8]) -> #var_type #get_block
+        pub fn #fn_name (data: &[u8]) -> Result<#var_type, Error> #get_block
     }.into()
 }
\ No newline at end of file
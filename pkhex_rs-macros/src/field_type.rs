@@ -0,0 +1,417 @@
+use proc_macro2::{TokenStream, Literal};
+use syn::{Attribute, Ident, Type, Expr, ExprBinary, ExprRange, Lit, RangeLimits, Token, spanned::Spanned};
+use quote::{quote, format_ident};
+
+/// The kinds of field the macros can read and write
+pub(crate) enum FieldType {
+    /// `u8`, `i8` and `bool`, a single byte without endianess
+    Byte(Ident),
+    /// `u16`, `i16`, `u32`, `i32`, `u64` and `i64`
+    Integer(Ident),
+    /// `[u8; N]`, copied as is
+    ByteArray(Expr)
+}
+
+pub(crate) const SUPPORTED_TYPES: &str = "The type must be u8, i8, u16, i16, u32, i32, u64, i64, bool or a byte array like [u8; 4]";
+pub(crate) const SUPPORTED_BIT_TYPES: &str = "Bit fields must be u8, u16, u32, u64 or a single bit bool";
+
+/// The type an enum field is stored as, given with `#[raw(u8)]`
+pub(crate) fn raw_type(attrs: &[Attribute]) -> syn::Result<Option<Type>> {
+    attrs.iter()
+        .find(|attr| attr.path().is_ident("raw"))
+        .map(|attr| attr.parse_args::<Type>())
+        .transpose()
+}
+
+/// Converts the raw value returned by `getter` into the enum with `TryFrom`,
+/// its error must convert into `Error`
+pub(crate) fn enum_getter(enum_type: &Type, raw_type: &Type, getter: &TokenStream) -> TokenStream {
+    quote! {{
+        let raw: Result<#raw_type, Error> = #getter;
+        let value: #enum_type = ::core::convert::TryFrom::try_from(raw?)?;
+        Ok(value)
+    }}
+}
+
+/// Like [`enum_getter`] for the raw value returned without a `Result` by [`FieldType::array_getter`]
+pub(crate) fn enum_array_getter(enum_type: &Type, raw_type: &Type, getter: &TokenStream) -> TokenStream {
+    quote! {{
+        let raw: #raw_type = #getter;
+        let value: #enum_type = ::core::convert::TryFrom::try_from(raw)?;
+        Ok(value)
+    }}
+}
+
+/// Converts the enum in `value` into its raw value with `Into` before running `setter`
+pub(crate) fn enum_setter(raw_type: &Type, setter: &TokenStream) -> TokenStream {
+    quote! {{
+        let value: #raw_type = ::core::convert::Into::into(value);
+        #setter
+    }}
+}
+
+/// The key of a field XORed with it, given with `#[xor(key)]`, the accessors take it
+/// as a parameter named `key` of the type of the field, or of the type given with
+/// `#[xor(key: u32)]` which is truncated to the field
+pub(crate) struct XorKey {
+    pub name: Ident,
+    pub key_type: Option<Type>
+}
+
+impl XorKey {
+    pub fn from_attributes(attrs: &[Attribute]) -> syn::Result<Option<Self>> {
+        attrs.iter()
+            .find(|attr| attr.path().is_ident("xor"))
+            .map(|attr| attr.parse_args_with(|input: syn::parse::ParseStream| {
+                let name = input.parse()?;
+                let key_type = if input.parse::<Option<Token![:]>>()?.is_some() { Some(input.parse()?) } else { None };
+                Ok(XorKey { name, key_type })
+            }))
+            .transpose()
+    }
+
+    /// Only whole integers can be XORed, not bit fields, enums, `bool`s or arrays
+    pub fn check(&self, kind: &FieldType, bits: Option<&BitRange>, raw: Option<&Type>) -> syn::Result<()> {
+        match kind {
+            FieldType::Byte(ident) if ident == "bool" => {},
+            FieldType::Byte(_) | FieldType::Integer(_) if bits.is_none() && raw.is_none() => return Ok(()),
+            _ => {}
+        }
+
+        Err(syn::Error::new_spanned(&self.name, "Only integer fields that aren't bit fields or enums can be XORed with a key"))
+    }
+
+    /// The key parameter of the accessors, followed by a comma
+    pub fn param(&self, field_type: &Type) -> TokenStream {
+        let (name, key_type) = (&self.name, self.key_type.as_ref().unwrap_or(field_type));
+        quote!( #name: #key_type, )
+    }
+
+    fn key(&self, field_type: &Type) -> TokenStream {
+        let name = &self.name;
+        match self.key_type {
+            Some(_) => quote!( (#name as #field_type) ),
+            None => quote!( #name )
+        }
+    }
+
+    /// XORs the value returned by `getter` with the key
+    pub fn getter(&self, field_type: &Type, getter: &TokenStream) -> TokenStream {
+        let key = self.key(field_type);
+        quote! {{
+            let value: Result<#field_type, Error> = #getter;
+            Ok(value? ^ #key)
+        }}
+    }
+
+    /// XORs the value returned without a `Result` by [`FieldType::array_getter`] with the key
+    pub fn array_getter(&self, field_type: &Type, getter: &TokenStream) -> TokenStream {
+        let key = self.key(field_type);
+        quote! { (#getter) ^ #key }
+    }
+
+    /// XORs `value` with the key before running `setter`
+    pub fn setter(&self, field_type: &Type, setter: &TokenStream) -> TokenStream {
+        let key = self.key(field_type);
+        quote! {{
+            let value = value ^ #key;
+            #setter
+        }}
+    }
+}
+
+/// The bits `start..end` of a bit field, counted from the lowest bit of the first byte
+/// for little-endian fields and from its highest bit for big-endian ones, so the bits
+/// don't depend on the integer read to access them
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BitRange {
+    pub start: usize,
+    pub end: usize
+}
+
+impl BitRange {
+    /// Reads a range of integer literals like `0..5`
+    pub fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        let bound = |bound: &Option<Box<Expr>>| match bound.as_deref() {
+            Some(Expr::Lit(lit)) => match &lit.lit {
+                Lit::Int(int) => int.base10_parse::<usize>(),
+                _ => Err(syn::Error::new(lit.span(), "Expected an integer"))
+            },
+            _ => Err(syn::Error::new_spanned(expr, "The bits must be a range of integers like `0..5`"))
+        };
+
+        let (start, end) = match expr {
+            Expr::Range(ExprRange { start, end, limits: RangeLimits::HalfOpen(_), .. }) => (bound(start)?, bound(end)?),
+            _ => return Err(syn::Error::new_spanned(expr, "The bits must be a range of integers like `0..5`"))
+        };
+
+        if start >= end || end > 64 {
+            return Err(syn::Error::new_spanned(expr, "The bits must be a non empty range within the 64 bits of a u64"));
+        }
+
+        Ok(BitRange { start, end })
+    }
+
+    /// Splits `0x48[0..5]` into the offset and its bits, the index binds tighter than
+    /// the operators so `0x46+2[0..5]` is split by following the right side of the operations
+    pub fn split_offset(expr: Expr) -> syn::Result<(Expr, Option<Self>)> {
+        match expr {
+            Expr::Index(index) => Ok((*index.expr, Some(BitRange::from_expr(&index.index)?))),
+            Expr::Binary(ExprBinary { attrs, left, op, right }) => {
+                let (right, bits) = BitRange::split_offset(*right)?;
+                Ok((Expr::Binary(ExprBinary { attrs, left, op, right: Box::new(right) }), bits))
+            },
+            expr => Ok((expr, None))
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// The smallest unsigned integer holding the bits, read and written to access them,
+    /// and its size in bytes
+    pub fn container(&self) -> (Ident, usize) {
+        let size = match self.end {
+            0..=8 => 1,
+            9..=16 => 2,
+            17..=32 => 4,
+            _ => 8
+        };
+
+        (format_ident!("u{}", size * 8), size)
+    }
+
+    /// Positions of the bits relative to the first byte of the field, with the
+    /// lowest bit of every byte first, so fields in different endianess can be compared
+    pub fn positions(&self, big_endian: bool) -> Vec<usize> {
+        (self.start..self.end).map(|bit| if big_endian { bit / 8 * 8 + 7 - bit % 8 } else { bit }).collect()
+    }
+
+    /// Position of the lowest bit of the field in the integer holding it
+    fn shift(&self, big_endian: bool) -> usize {
+        let (_, size) = self.container();
+        if big_endian { size * 8 - self.end } else { self.start }
+    }
+
+    fn mask(&self, big_endian: bool) -> Literal {
+        let mask = ((1u128 << self.width()) - 1) << self.shift(big_endian);
+        Literal::u128_unsuffixed(mask)
+    }
+
+    /// The shift and mask of the bits in the integer read in `endianess`
+    fn shift_and_mask(&self, endianess: &Ident) -> (usize, Literal) {
+        let big_endian = endianess == "be";
+        (self.shift(big_endian), self.mask(big_endian))
+    }
+}
+
+impl FieldType {
+    pub fn from_type(field_type: &Type) -> Option<Self> {
+        match field_type {
+            Type::Path(verbatim) => {
+                let ident = verbatim.path.get_ident()?;
+                match ident.to_string().as_str() {
+                    "u8" | "i8" | "bool" => Some(FieldType::Byte(ident.clone())),
+                    "u16" | "i16" | "u32" | "i32" | "u64" | "i64" => Some(FieldType::Integer(ident.clone())),
+                    _ => None
+                }
+            },
+            Type::Array(array) => match &*array.elem {
+                Type::Path(element) if element.path.is_ident("u8") => Some(FieldType::ByteArray(array.len.clone())),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    /// Reads the type of a field and checks that it can hold its bits,
+    /// the errors point at the type
+    pub fn from_field(field_type: &Type, bits: Option<&BitRange>) -> syn::Result<Self> {
+        let kind = FieldType::from_type(field_type).ok_or_else(|| syn::Error::new_spanned(field_type, SUPPORTED_TYPES))?;
+        if let Some(bits) = bits {
+            kind.check_bits(bits).map_err(|message| syn::Error::new_spanned(field_type, message))?;
+        }
+
+        Ok(kind)
+    }
+
+    /// Checks that the type can hold the bits of a bit field
+    pub fn check_bits(&self, bits: &BitRange) -> Result<(), &'static str> {
+        let type_bits = match self {
+            FieldType::Byte(ident) if ident == "bool" => 1,
+            FieldType::Byte(ident) if ident == "u8" => 8,
+            FieldType::Integer(ident) if ident == "u16" => 16,
+            FieldType::Integer(ident) if ident == "u32" => 32,
+            FieldType::Integer(ident) if ident == "u64" => 64,
+            _ => return Err(SUPPORTED_BIT_TYPES)
+        };
+
+        if bits.width() > type_bits {
+            return Err(if type_bits == 1 { SUPPORTED_BIT_TYPES } else { "The bits don't fit in the type of the field" });
+        }
+
+        Ok(())
+    }
+
+    /// Size in bytes of the field, `None` for arrays whose length isn't a literal
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            FieldType::Byte(_) => Some(1),
+            FieldType::Integer(ident) => match ident.to_string().as_str() {
+                "u16" | "i16" => Some(2),
+                "u32" | "i32" => Some(4),
+                _ => Some(8)
+            },
+            FieldType::ByteArray(Expr::Lit(len)) => match &len.lit {
+                Lit::Int(int) => int.base10_parse().ok(),
+                _ => None
+            },
+            FieldType::ByteArray(_) => None
+        }
+    }
+
+    /// Name of the `SliceUtils` method reading or writing the field, `get` or `set`
+    fn method(&self, action: &str, endianess: &Ident) -> Ident {
+        match self {
+            FieldType::Byte(ident) => format_ident!("{}_{}_offset", action, ident),
+            FieldType::Integer(ident) => format_ident!("{}_{}_{}_offset", action, ident, endianess),
+            FieldType::ByteArray(_) => format_ident!("{}_array_offset", action)
+        }
+    }
+
+    /// Call to `SliceUtils` returning `Result<TYPE, Error>`,
+    /// bit fields read their integer and mask the bits out of it
+    pub fn getter(&self, endianess: &Ident, data: TokenStream, offset: &Expr, bits: Option<&BitRange>) -> TokenStream {
+        let bits = match bits {
+            Some(bits) => bits,
+            None => {
+                let get_fn = self.method("get", endianess);
+                return quote! { SliceUtils:: #get_fn (#data, #offset) };
+            }
+        };
+
+        let (container, _) = bits.container();
+        let get_fn = FieldType::container_method("get", &container, endianess);
+        let (shift, mask) = bits.shift_and_mask(endianess);
+        let value = match self {
+            FieldType::Byte(ident) if ident == "bool" => quote! { word & #mask != 0 },
+            _ => {
+                let field_type = self.type_ident();
+                quote! { ((word & #mask) >> #shift) as #field_type }
+            }
+        };
+
+        quote! {{
+            let word = SliceUtils:: #get_fn (#data, #offset)?;
+            Ok(#value)
+        }}
+    }
+
+    /// Call to `SliceUtils` writing `value` and returning `Result<(), Error>`,
+    /// bit fields read their integer, replace the bits and write it back,
+    /// dropping the bits of `value` that don't fit
+    pub fn setter(&self, endianess: &Ident, data: TokenStream, offset: &Expr, value: TokenStream, bits: Option<&BitRange>) -> TokenStream {
+        let bits = match bits {
+            Some(bits) => bits,
+            None => {
+                let set_fn = self.method("set", endianess);
+                return quote! { SliceUtils:: #set_fn (#data, #offset, #value) };
+            }
+        };
+
+        let (container, _) = bits.container();
+        let get_fn = FieldType::container_method("get", &container, endianess);
+        let set_fn = FieldType::container_method("set", &container, endianess);
+        let (shift, mask) = bits.shift_and_mask(endianess);
+
+        quote! {{
+            let word = SliceUtils:: #get_fn (#data, #offset)?;
+            let word = (word & !#mask) | (((#value as #container) << #shift) & #mask);
+            SliceUtils:: #set_fn (#data, #offset, word)
+        }}
+    }
+
+    /// Expression reading the field out of the array `data`, whose size is checked to hold it,
+    /// so the value is returned without a `Result`
+    pub fn array_getter(&self, endianess: &Ident, data: TokenStream, offset: &Expr, bits: Option<&BitRange>) -> TokenStream {
+        let bits = match bits {
+            Some(bits) => bits,
+            None => return match self {
+                FieldType::Byte(ident) if ident == "bool" => quote! { #data[#offset] != 0 },
+                FieldType::Byte(ident) if ident == "i8" => quote! { #data[#offset] as i8 },
+                FieldType::Byte(_) => quote! { #data[#offset] },
+                FieldType::Integer(ident) => FieldType::array_read(ident, endianess, &data, offset),
+                FieldType::ByteArray(_) => quote! { ::core::array::from_fn(|i| #data[(#offset) + i]) }
+            }
+        };
+
+        let (container, _) = bits.container();
+        let word = FieldType::array_read(&container, endianess, &data, offset);
+        let (shift, mask) = bits.shift_and_mask(endianess);
+        match self {
+            FieldType::Byte(ident) if ident == "bool" => quote! { #word & #mask != 0 },
+            _ => {
+                let field_type = self.type_ident();
+                quote! { ((#word & #mask) >> #shift) as #field_type }
+            }
+        }
+    }
+
+    /// Statements writing `value` into the array `data`, whose size is checked to hold it,
+    /// bit fields only replace their own bits like with [`FieldType::setter`]
+    pub fn array_setter(&self, endianess: &Ident, data: TokenStream, offset: &Expr, value: TokenStream, bits: Option<&BitRange>) -> TokenStream {
+        let bits = match bits {
+            Some(bits) => bits,
+            None => return match self {
+                FieldType::Byte(ident) if ident == "u8" => quote! { #data[#offset] = #value; },
+                FieldType::Byte(_) => quote! { #data[#offset] = #value as u8; },
+                FieldType::Integer(_) => FieldType::array_write(endianess, &data, offset, value),
+                FieldType::ByteArray(_) => quote! {
+                    for (i, byte) in #value.into_iter().enumerate() {
+                        #data[(#offset) + i] = byte;
+                    }
+                }
+            }
+        };
+
+        let (container, _) = bits.container();
+        let word = FieldType::array_read(&container, endianess, &data, offset);
+        let (shift, mask) = bits.shift_and_mask(endianess);
+        let write = FieldType::array_write(endianess, &data, offset, quote!(word));
+
+        quote! {
+            let word = (#word & !#mask) | (((#value as #container) << #shift) & #mask);
+            #write
+        }
+    }
+
+    fn array_read(int_type: &Ident, endianess: &Ident, data: &TokenStream, offset: &Expr) -> TokenStream {
+        let from_bytes = format_ident!("from_{}_bytes", endianess);
+        quote! { #int_type::#from_bytes(::core::array::from_fn(|i| #data[(#offset) + i])) }
+    }
+
+    fn array_write(endianess: &Ident, data: &TokenStream, offset: &Expr, value: TokenStream) -> TokenStream {
+        let to_bytes = format_ident!("to_{}_bytes", endianess);
+        quote! {
+            for (i, byte) in #value.#to_bytes().into_iter().enumerate() {
+                #data[(#offset) + i] = byte;
+            }
+        }
+    }
+
+    fn type_ident(&self) -> Option<&Ident> {
+        match self {
+            FieldType::Byte(ident) | FieldType::Integer(ident) => Some(ident),
+            FieldType::ByteArray(_) => None
+        }
+    }
+
+    fn container_method(action: &str, container: &Ident, endianess: &Ident) -> Ident {
+        if container == "u8" {
+            format_ident!("{}_u8_offset", action)
+        } else {
+            format_ident!("{}_{}_{}_offset", action, container, endianess)
+        }
+    }
+}
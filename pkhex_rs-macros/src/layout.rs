@@ -0,0 +1,199 @@
+use proc_macro2::TokenStream;
+use syn::{Attribute, Expr, ExprBinary, BinOp, Ident, Lit, Type, ext::IdentExt};
+use quote::quote;
+
+use crate::field_type::BitRange;
+use crate::kw;
+
+/// What to do when two fields share bytes without one of them being marked as `#[union]`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OverlapPolicy {
+    Deny,
+    Allow
+}
+
+/// The layout options given with `#[size(...)]` and `#[overlap(...)]`
+pub(crate) struct LayoutOptions {
+    pub size: Option<Expr>,
+    pub overlap: OverlapPolicy
+}
+
+/// The bytes used by a field, as needed to check the layout
+pub(crate) struct FieldLayout<'a> {
+    pub var_name: &'a Ident,
+    pub offset: &'a Expr,
+    pub var_type: &'a Type,
+    /// The size if the macro knows it, otherwise it's left to the compiler,
+    /// bit fields take the size of the integer holding their bits
+    pub size: Option<usize>,
+    pub bits: Option<BitRange>,
+    pub big_endian: bool,
+    pub union: bool
+}
+
+impl FieldLayout<'_> {
+    /// Start and end of the field if both are known by the macro
+    fn range(&self) -> Option<(usize, usize)> {
+        let start = eval_offset(self.offset)?;
+        Some((start, start.checked_add(self.size?)?))
+    }
+
+    /// Absolute positions of the bits used by the field if the macro knows them
+    fn bit_positions(&self) -> Option<Vec<usize>> {
+        let (start, end) = self.range()?;
+        Some(match &self.bits {
+            Some(bits) => bits.positions(self.big_endian).into_iter().map(|bit| start * 8 + bit).collect(),
+            None => (start * 8..end * 8).collect()
+        })
+    }
+
+    /// Whether the fields use the same bits, `None` if it can only be known by the compiler
+    fn overlaps(&self, other: &FieldLayout) -> Option<bool> {
+        if let (Some(bits), Some(other_bits)) = (self.bit_positions(), other.bit_positions()) {
+            return Some(bits.iter().any(|bit| other_bits.contains(bit)));
+        }
+
+        // Bit fields at the same offset only depend on their bits
+        let (offset, other_offset) = (self.offset, other.offset);
+        match (&self.bits, &other.bits) {
+            (Some(bits), Some(other_bits)) if quote!( #offset ).to_string() == quote!( #other_offset ).to_string() => {
+                let other_positions = other_bits.positions(other.big_endian);
+                Some(bits.positions(self.big_endian).iter().any(|bit| other_positions.contains(bit)))
+            },
+            _ => None
+        }
+    }
+
+    fn size_tokens(&self) -> TokenStream {
+        let var_type = self.var_type;
+        match self.size {
+            Some(size) => quote!( #size ),
+            None => quote!( ::core::mem::size_of::<#var_type>() )
+        }
+    }
+}
+
+impl LayoutOptions {
+    /// Reads the layout options out of the attributes, leaving the others alone
+    pub fn from_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = LayoutOptions {
+            size: None,
+            overlap: OverlapPolicy::Deny
+        };
+
+        for attr in attrs {
+            if attr.path().is_ident("size") {
+                options.size = Some(attr.parse_args::<Expr>()?);
+            } else if attr.path().is_ident("overlap") {
+                options.overlap = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                    let lookahead = input.lookahead1();
+                    if lookahead.peek(kw::allow) {
+                        input.call(Ident::parse_any)?;
+                        Ok(OverlapPolicy::Allow)
+                    } else if lookahead.peek(kw::deny) {
+                        input.call(Ident::parse_any)?;
+                        Ok(OverlapPolicy::Deny)
+                    } else {
+                        Err(lookahead.error())
+                    }
+                })?;
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Whether the attributes mark the field as a `#[union]`, allowed to overlap other fields
+pub(crate) fn is_union(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("union"))
+}
+
+/// Computes offsets made of integer literals, `+`, `-` and `*`,
+/// `None` for anything else like constants
+fn eval_offset(expr: &Expr) -> Option<usize> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_parse().ok(),
+            _ => None
+        },
+        Expr::Paren(paren) => eval_offset(&paren.expr),
+        Expr::Group(group) => eval_offset(&group.expr),
+        Expr::Binary(ExprBinary { left, op, right, .. }) => {
+            let (left, right) = (eval_offset(left)?, eval_offset(right)?);
+            match op {
+                BinOp::Add(_) => left.checked_add(right),
+                BinOp::Sub(_) => left.checked_sub(right),
+                BinOp::Mul(_) => left.checked_mul(right),
+                _ => None
+            }
+        },
+        _ => None
+    }
+}
+
+/// Checks that the fields fit in the declared size and only overlap when allowed
+///
+/// Offsets known by the macro are checked right away and reported with `compile_error!`,
+/// the others are checked by the compiler with a `const` assertion that the returned
+/// tokens hold, so they must be placed where a statement or item can go
+pub(crate) fn check_layout(options: &LayoutOptions, fields: &[FieldLayout]) -> syn::Result<TokenStream> {
+    let mut errors: Option<syn::Error> = None;
+    let mut push_error = |error: syn::Error| match errors.as_mut() {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error)
+    };
+    let mut assertions = Vec::new();
+
+    if let Some(struct_size) = &options.size {
+        for field in fields {
+            let (offset, size) = (field.offset, field.size_tokens());
+            match (field.range(), eval_offset(struct_size)) {
+                (Some((_, end)), Some(struct_end)) => if end > struct_end {
+                    push_error(syn::Error::new_spanned(offset, format!(
+                        "The field `{}` ends at {:#X}, past the declared size of {:#X}", field.var_name, end, struct_end)));
+                },
+                _ => {
+                    let message = format!("The field `{}` ends past the declared size", field.var_name);
+                    assertions.push(quote! {
+                        #[allow(clippy::int_plus_one)]
+                        const _: () = assert!((#offset) + #size <= (#struct_size), #message);
+                    });
+                }
+            }
+        }
+    }
+
+    if options.overlap == OverlapPolicy::Deny {
+        for (i, first) in fields.iter().enumerate() {
+            for second in fields.iter().skip(i + 1).filter(|second| !first.union && !second.union) {
+                let (first_offset, second_offset) = (first.offset, second.offset);
+                let (first_size, second_size) = (first.size_tokens(), second.size_tokens());
+                match first.overlaps(second) {
+                    Some(overlaps) => {
+                        if overlaps {
+                            push_error(syn::Error::new_spanned(second_offset, format!(
+                                "The field `{}` overlaps `{}`, mark one of them as `#[union]` if it's intended",
+                                second.var_name, first.var_name)));
+                        }
+                    },
+                    None => {
+                        let message = format!("The field `{}` overlaps `{}`", second.var_name, first.var_name);
+                        assertions.push(quote! {
+                            #[allow(clippy::int_plus_one)]
+                            const _: () = assert!(
+                                (#first_offset) + #first_size <= (#second_offset) || (#second_offset) + #second_size <= (#first_offset),
+                                #message
+                            );
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(quote! { #(#assertions)* })
+    }
+}
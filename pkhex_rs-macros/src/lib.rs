@@ -1,6 +1,10 @@
+mod byte_struct_macro;
 mod bytes_macro;
 mod data_getset_macro;
+mod field_type;
+mod layout;
 
+use byte_struct_macro::ByteStruct;
 use bytes_macro::BytesGetSetCollection;
 use data_getset_macro::DataGetSet;
 use proc_macro::TokenStream;
@@ -10,6 +14,80 @@ mod kw {
     syn::custom_keyword!(get);
     syn::custom_keyword!(be);
     syn::custom_keyword!(le);
+    syn::custom_keyword!(allow);
+    syn::custom_keyword!(deny);
+}
+
+/// Generates a read view and a mutable view over the bytes of the struct,
+/// named `<STRUCT>View` and `<STRUCT>ViewMut`, so fields are read and written
+/// in place instead of being copied out
+/// 
+/// Every field needs an `#[offset(...)]` attribute, the `#[endian(...)]` attribute
+/// can be ommited and defaults to little-endian, the valid endianess are `le` and `be`
+/// 
+/// Bit fields are declared with `#[bits(START..END)]` next to their offset,
+/// as explained in [`byte_parser_proc!`]
+/// 
+/// Enum fields are declared with the integer they're stored as, like `#[raw(u8)]`,
+/// and get `<FIELD>_raw` and `set_<FIELD>_raw` accessors for the values the enum doesn't cover.
+/// Fields XORed with a key are marked with `#[xor(key)]`, as explained in [`byte_parser_proc!`]
+/// 
+/// Like in [`byte_parser_proc!`], the struct can declare its size with `#[size(...)]`
+/// and fields overlapping each other must be marked as `#[union]`,
+/// unless the struct has `#[overlap(allow)]`
+/// 
+/// The getters return `Result<TYPE, Error>` and the setters `Result<(), Error>`,
+/// so both `SliceUtils` and the `Error` returned by its methods must be in scope
+/// 
+/// # Examples
+/// ```
+/// # use pkhex_rs_macros::ByteStruct;
+/// # #[derive(Debug, PartialEq)]
+/// # pub struct Error;
+/// # pub trait SliceUtils {
+/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error>;
+/// # fn get_mutable_offset(&mut self, offset: usize, byte_quantity: usize) -> Result<&mut [u8], Error>;
+/// # fn get_u8_offset(&self, offset: usize) -> Result<u8, Error> { Ok(self.get_offset(offset, 1)?[0]) }
+/// # fn set_u8_offset(&mut self, offset: usize, value: u8) -> Result<(), Error> { self.get_mutable_offset(offset, 1)?[0] = value; Ok(()) }
+/// # fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error> { Ok(u16::from_le_bytes(self.get_offset(offset, 2)?.try_into().unwrap())) }
+/// # fn set_u16_le_offset(&mut self, offset: usize, value: u16) -> Result<(), Error> { self.get_mutable_offset(offset, 2)?.copy_from_slice(&value.to_le_bytes()); Ok(()) }
+/// # fn get_u32_be_offset(&self, offset: usize) -> Result<u32, Error> { Ok(u32::from_be_bytes(self.get_offset(offset, 4)?.try_into().unwrap())) }
+/// # fn set_u32_be_offset(&mut self, offset: usize, value: u32) -> Result<(), Error> { self.get_mutable_offset(offset, 4)?.copy_from_slice(&value.to_be_bytes()); Ok(()) }
+/// # }
+/// # 
+/// # impl SliceUtils for [u8] {
+/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error> { self.get(offset..offset + byte_quantity).ok_or(Error) }
+/// # fn get_mutable_offset(&mut self, offset: usize, byte_quantity: usize) -> Result<&mut [u8], Error> { self.get_mut(offset..offset + byte_quantity).ok_or(Error) }
+/// # }
+/// 
+/// #[derive(ByteStruct)]
+/// #[size(0x7)]
+/// pub struct Footer {
+///     #[offset(0x0)]
+///     id: u16,
+///     #[offset(0x2)]
+///     #[endian(be)]
+///     index: u32,
+///     #[offset(0x6)]
+///     version: u8,
+/// }
+/// 
+/// let mut data = [0u8; 7];
+/// let mut footer = Footer::view_mut(&mut data);
+/// footer.set_index(42)?;
+/// footer.set_version(3)?;
+/// 
+/// # assert_eq!(Footer::view(&data).index()?, 42);
+/// # assert_eq!(data, [0, 0, 0, 0, 0, 42, 3]);
+/// # assert!(Footer::view(&data[..4]).index().is_err());
+/// # assert!(FooterViewMut::new(&mut data[..1]).set_id(1).is_err());
+/// # Ok::<(), Error>(())
+/// ```
+#[proc_macro_derive(ByteStruct, attributes(offset, bits, endian, raw, xor, size, overlap, union))]
+pub fn byte_struct_derive(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as ByteStruct);
+
+    byte_struct_macro::expand_byte_struct(input)
 }
 
 /// The endianess can be ommited, doing so it will default to little-endian,
@@ -17,37 +95,173 @@ mod kw {
 /// 
 /// Multiple fields can be declared at once, separeted by comma `,`
 /// 
-/// The types must have a `get_<TYPE>_<ENDIANESS>_offset` method in the [`pkhex:utils::SliceUtils`]!
+/// The size of the data can be declared with `#![size(...)]` before the fields,
+/// any field ending past it fails to compile. Fields that share bytes also fail to compile,
+/// unless one of them is marked as `#[union]` or the check is turned off with `#![overlap(allow)]`
+/// 
+/// With a declared size, every field also gets `get_<FIELD>_from_array` and `set_<FIELD>_from_array`,
+/// which take an array at least as large as the size, checked when compiling. As the field is known
+/// to fit they read and write it directly, without the `Result`, which only the enum getters still return
+/// 
+/// The valid types are `u8`, `i8`, `u16`, `i16`, `u32`, `i32`, `u64`, `i64`, `bool` and byte arrays like `[u8; 4]`,
+/// read and written with the `get_<TYPE>_<ENDIANESS>_offset` and `set_<TYPE>_<ENDIANESS>_offset` methods
+/// of the [`pkhex:utils::SliceUtils`], or `get_<TYPE>_offset` for the single byte types
+/// and `get_array_offset` for the arrays, which have no endianess
+/// 
+/// Bit fields take the bits `START..END` counted from the offset, like `hp_iv: u8@0x48[0..5]`
+/// which reads the lowest 5 bits of the byte at `0x48`, and `attack_iv: u8@0x48[5..10]` the next 5
+/// of the little-endian `u16` at `0x48`. Big-endian fields count the bits from the highest bit
+/// of the first byte instead, so `u8@0x10[0..4]#be` reads the high nibble of the byte at `0x10`
+/// and `u16@0x10[20..32]#be` the lowest 12 bits of the big-endian `u32` at `0x10`.
+/// They can be `u8`, `u16`, `u32`, `u64`, or `bool` for single bits, and their setters
+/// only replace their own bits, dropping the bits of the value that don't fit
+/// 
+/// Enum fields are marked with the integer they're stored as, like `#[raw(u8)] gender: Gender@0x46[15..16]`,
+/// which is converted with `TryFrom<u8>` when reading, its error must convert into `Error`,
+/// and with `Into<u8>` when writing. They also get `get_<FIELD>_raw_from_bytes` and
+/// `set_<FIELD>_raw_from_bytes` to read and write the values the enum doesn't cover
+/// 
+/// Integer fields XORed with a key, like the money of Emerald, are marked with `#[xor(key)]`,
+/// their getter and setter take the key as a parameter named `key` right after the data.
+/// The key has the type of the field, or the type given with `#[xor(key: u32)]`
+/// which is truncated to the field, as the coins only use the lower half of the security key
+/// 
+/// The getters return `Result<TYPE, Error>` and the setters `Result<(), Error>`,
+/// so both `SliceUtils` and the `Error` returned by its methods must be in scope
 /// 
 /// # Examples
 /// ```
 /// # use pkhex_rs_macros::byte_parser_proc;
-/// # use std::array::TryFromSliceError;
+/// # #[derive(Debug)]
+/// # pub struct Error;
 /// # pub trait SliceUtils {
-/// # fn write_into(&mut self, data: &[u8], offset: usize);
-/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> &[u8];
-/// # fn get_u16_le(&self) -> Result<u16, TryFromSliceError>;
-/// # fn get_u32_le(&self) -> Result<u32, TryFromSliceError>;
-/// # fn get_u16_le_offset(&self, offset: usize) -> Result<u16, TryFromSliceError>;
-/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, TryFromSliceError>;
+/// # fn set_u16_le_offset(&mut self, offset: usize, value: u16) -> Result<(), Error>;
+/// # fn set_u32_le_offset(&mut self, offset: usize, value: u32) -> Result<(), Error>;
+/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error>;
+/// # fn get_u16_le(&self) -> Result<u16, Error>;
+/// # fn get_u32_le(&self) -> Result<u32, Error>;
+/// # fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error>;
+/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error>;
+/// # fn get_u8_offset(&self, offset: usize) -> Result<u8, Error> { Ok(self.get_offset(offset, 1)?[0]) }
+/// # fn set_u8_offset(&mut self, offset: usize, value: u8) -> Result<(), Error>;
 /// # }
 /// # 
 /// # impl SliceUtils for [u8] {
-/// # fn write_into(&mut self, data: &[u8], offset: usize) { self[offset..(data.len())].copy_from_slice(data);}
+/// # fn set_u8_offset(&mut self, offset: usize, value: u8) -> Result<(), Error> { *self.get_mut(offset).ok_or(Error)? = value; Ok(()) }
+/// # fn set_u16_le_offset(&mut self, offset: usize, value: u16) -> Result<(), Error> { self.get_mut(offset..offset + 2).ok_or(Error)?.copy_from_slice(&value.to_le_bytes()); Ok(()) }
+/// # fn set_u32_le_offset(&mut self, offset: usize, value: u32) -> Result<(), Error> { self.get_mut(offset..offset + 4).ok_or(Error)?.copy_from_slice(&value.to_le_bytes()); Ok(()) }
 /// # 
-/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> &[u8] { &self[offset..offset + byte_quantity] }
-/// # fn get_u16_le(&self) -> Result<u16, TryFromSliceError> { Ok(u16::from_le_bytes(self[..2].try_into()?)) }
-/// # fn get_u32_le(&self) -> Result<u32, TryFromSliceError> { Ok(u32::from_le_bytes(self[..4].try_into()?)) }
-/// # fn get_u16_le_offset(&self, offset: usize) -> Result<u16, TryFromSliceError> { self.get_offset(offset, 2).get_u16_le() }
-/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, TryFromSliceError> { self.get_offset(offset, 4).get_u32_le() }
+/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error> { self.get(offset..offset + byte_quantity).ok_or(Error) }
+/// # fn get_u16_le(&self) -> Result<u16, Error> { Ok(u16::from_le_bytes(self.get_offset(0, 2)?.try_into().map_err(|_| Error)?)) }
+/// # fn get_u32_le(&self) -> Result<u32, Error> { Ok(u32::from_le_bytes(self.get_offset(0, 4)?.try_into().map_err(|_| Error)?)) }
+/// # fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error> { self.get_offset(offset, 2)?.get_u16_le() }
+/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error> { self.get_offset(offset, 4)?.get_u32_le() }
 /// # }
 /// 
-/// byte_parser_proc! { field_1: u32@0x0 }
-/// byte_parser_proc! { field_2: u16@0x0#le, field_3: u16@0x0 }
+/// byte_parser_proc! { #![size(0x4)] field_1: u32@0x0 }
+/// byte_parser_proc! { #[union] field_2: u16@0x0#le, field_3: u16@0x0 }
+/// byte_parser_proc! { low_bits: u8@0x0[0..4], high_bits: u16@0x0[4..16], flag: bool@0x0+2[0..1] }
+/// 
+/// #[derive(Debug, PartialEq)]
+/// enum Gender { Male, Female }
 /// 
-/// # assert_eq!(get_field_1_from_bytes(&u32::to_le_bytes(0x2A)), 42);
-/// # assert_eq!(get_field_2_from_bytes(&u32::to_le_bytes(0x2A)), 42);
-/// # assert_eq!(get_field_3_from_bytes(&u32::to_le_bytes(0x2A)), 42);
+/// impl TryFrom<u8> for Gender {
+///     type Error = Error;
+///     fn try_from(value: u8) -> Result<Self, Error> {
+///         match value {
+///             0 => Ok(Gender::Male),
+///             1 => Ok(Gender::Female),
+///             _ => Err(Error)
+///         }
+///     }
+/// }
+/// 
+/// impl From<Gender> for u8 {
+///     fn from(gender: Gender) -> u8 {
+///         gender as u8
+///     }
+/// }
+/// 
+/// byte_parser_proc! { #[raw(u8)] gender: Gender@0x3[6..8] }
+/// byte_parser_proc! { #[union] #[xor(key: u32)] coins: u16@0x0 }
+/// 
+/// # assert_eq!(get_field_1_from_bytes(&u32::to_le_bytes(0x2A))?, 42);
+/// # assert_eq!(get_field_2_from_bytes(&u32::to_le_bytes(0x2A))?, 42);
+/// # assert_eq!(get_field_3_from_bytes(&u32::to_le_bytes(0x2A))?, 42);
+/// # assert!(get_field_1_from_bytes(&[0x2A]).is_err());
+/// # let mut data = [0u8; 4];
+/// # set_field_3_from_bytes(&mut data, 42)?;
+/// # assert_eq!(data, [42, 0, 0, 0]);
+/// # assert!(set_field_1_from_bytes(&mut data[1..], 42).is_err());
+/// # let mut array = [0u8; 6];
+/// # set_field_1_from_array(&mut array, 0x0102_0304);
+/// # assert_eq!(array, [4, 3, 2, 1, 0, 0]);
+/// # assert_eq!(get_field_1_from_array(&array), 0x0102_0304);
+/// # set_high_bits_from_bytes(&mut data, 0xFFF)?;
+/// # set_flag_from_bytes(&mut data, true)?;
+/// # assert_eq!(data, [0xFA, 0xFF, 1, 0]);
+/// # assert_eq!(get_low_bits_from_bytes(&data)?, 0xA);
+/// # assert_eq!(get_high_bits_from_bytes(&data)?, 0xFFF);
+/// # assert!(get_flag_from_bytes(&data)?);
+/// # set_gender_from_bytes(&mut data, Gender::Female)?;
+/// # assert_eq!(data[3], 0x40);
+/// # assert_eq!(get_gender_from_bytes(&data)?, Gender::Female);
+/// # set_gender_raw_from_bytes(&mut data, 3)?;
+/// # assert_eq!(get_gender_raw_from_bytes(&data)?, 3);
+/// # assert!(get_gender_from_bytes(&data).is_err());
+/// # set_coins_from_bytes(&mut data, 0xAAAA_5555, 100)?;
+/// # assert_eq!(data[..2], (100u16 ^ 0x5555).to_le_bytes());
+/// # assert_eq!(get_coins_from_bytes(&data, 0xAAAA_5555)?, 100);
+/// # Ok::<(), Error>(())
+/// ```
+/// 
+/// ```compile_fail
+/// # use pkhex_rs_macros::byte_parser_proc;
+/// # #[derive(Debug)]
+/// # pub struct Error;
+/// # pub trait SliceUtils {
+/// # fn set_u16_le_offset(&mut self, offset: usize, value: u16) -> Result<(), Error>;
+/// # fn set_u32_le_offset(&mut self, offset: usize, value: u32) -> Result<(), Error>;
+/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error>;
+/// # fn get_u16_le(&self) -> Result<u16, Error>;
+/// # fn get_u32_le(&self) -> Result<u32, Error>;
+/// # fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error>;
+/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error>;
+/// # }
+/// # 
+/// # impl SliceUtils for [u8] {
+/// # fn set_u16_le_offset(&mut self, offset: usize, value: u16) -> Result<(), Error> { self.get_mut(offset..offset + 2).ok_or(Error)?.copy_from_slice(&value.to_le_bytes()); Ok(()) }
+/// # fn set_u32_le_offset(&mut self, offset: usize, value: u32) -> Result<(), Error> { self.get_mut(offset..offset + 4).ok_or(Error)?.copy_from_slice(&value.to_le_bytes()); Ok(()) }
+/// # 
+/// # fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error> { self.get(offset..offset + byte_quantity).ok_or(Error) }
+/// # fn get_u16_le(&self) -> Result<u16, Error> { Ok(u16::from_le_bytes(self.get_offset(0, 2)?.try_into().map_err(|_| Error)?)) }
+/// # fn get_u32_le(&self) -> Result<u32, Error> { Ok(u32::from_le_bytes(self.get_offset(0, 4)?.try_into().map_err(|_| Error)?)) }
+/// # fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error> { self.get_offset(offset, 2)?.get_u16_le() }
+/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error> { self.get_offset(offset, 4)?.get_u32_le() }
+/// # }
+/// 
+/// byte_parser_proc! { #![size(0x4)]
+///     id: u32@0x0,
+///     high_id: u16@0x2 // `high_id` overlaps `id`
+/// }
+/// ```
+/// 
+/// ```compile_fail
+/// # use pkhex_rs_macros::byte_parser_proc;
+/// # pub struct Error;
+/// # pub trait SliceUtils {
+/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error>;
+/// # fn set_u32_le_offset(&mut self, offset: usize, value: u32) -> Result<(), Error>;
+/// # }
+/// # 
+/// # impl SliceUtils for [u8] {
+/// # fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error> { Err(Error) }
+/// # fn set_u32_le_offset(&mut self, offset: usize, value: u32) -> Result<(), Error> { Err(Error) }
+/// # }
+/// 
+/// byte_parser_proc! { #![size(0x8)] id: u32@0x4 }
+/// 
+/// let id = get_id_from_array(&[0u8; 6]); // The array is smaller than the declared size
 /// ```
 #[proc_macro]
 pub fn byte_parser_proc(tokens: TokenStream) -> TokenStream {
@@ -56,15 +270,21 @@ pub fn byte_parser_proc(tokens: TokenStream) -> TokenStream {
     bytes_macro::expand_byte_get_set(input)
 }
 
+/// The getter returns `Result<TYPE, Error>` with the `Error` in scope,
+/// so the block must evaluate to a `Result`
+/// 
 /// # Examples
 /// ```
 /// # use pkhex_rs_macros::data_get_set_proc;
+/// # #[derive(Debug)]
+/// # pub struct Error;
 /// 
 /// data_get_set_proc! { num: u32;
-///     get => { u32::from_le_bytes(data.try_into().unwrap()) }
+///     get => { Ok(u32::from_le_bytes(data.try_into().map_err(|_| Error)?)) }
 /// }
 /// 
-/// # assert_eq!(get_num_from_bytes(&u32::to_le_bytes(0x2A)), 42);
+/// # assert_eq!(get_num_from_bytes(&u32::to_le_bytes(0x2A))?, 42);
+/// # Ok::<(), Error>(())
 /// ```
 #[proc_macro]
 pub fn data_get_set_proc(tokens: TokenStream) -> TokenStream {
@@ -0,0 +1,178 @@
+use pkhex_rs::error::Error;
+use pkhex_rs::pkm::pk3::{GameVersion, Gender};
+use pkhex_rs::utils::SliceUtils;
+use pkhex_rs_macros::{byte_parser_proc, ByteStruct};
+
+#[test]
+fn typed_macro_fields() {
+    #[derive(ByteStruct)]
+    #[size(0x10)]
+    #[allow(dead_code)]
+    struct Record {
+        #[offset(0x0)]
+        level: u8,
+        #[offset(0x1)]
+        modifier: i8,
+        #[offset(0x2)]
+        #[endian(be)]
+        species: u16,
+        #[offset(0x4)]
+        seed: u64,
+        #[offset(0xC)]
+        name: [u8; 3],
+        #[offset(0xF)]
+        #[bits(0..1)]
+        is_egg: bool,
+        #[offset(0xF)]
+        #[bits(1..8)]
+        language: u8,
+    }
+
+    struct Fields;
+    impl Fields {
+        byte_parser_proc! { #![size(0x10)]
+            delta: i32@0x4#be,
+            high: i16@0xA#le
+        }
+    }
+
+    let mut bytes = [0u8; 0x10];
+    let mut record = Record::view_mut(&mut bytes);
+    record.set_level(100).unwrap();
+    record.set_modifier(-3).unwrap();
+    record.set_species(0x0115).unwrap();
+    record.set_seed(0x1122_3344_5566_7788).unwrap();
+    record.set_name([0xBB, 0xCC, 0xDD]).unwrap();
+    record.set_is_egg(true).unwrap();
+    record.set_language(2).unwrap();
+
+    assert_eq!(bytes, [100, 0xFD, 0x01, 0x15, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, 0xBB, 0xCC, 0xDD, 0x05]);
+
+    let record = Record::view(&bytes);
+    assert_eq!(record.level(), Ok(100));
+    assert_eq!(record.modifier(), Ok(-3));
+    assert_eq!(record.species(), Ok(0x0115));
+    assert_eq!(record.seed(), Ok(0x1122_3344_5566_7788));
+    assert_eq!(record.name(), Ok([0xBB, 0xCC, 0xDD]));
+    assert_eq!(record.is_egg(), Ok(true));
+    assert_eq!(record.language(), Ok(2));
+
+    assert_eq!(Fields::get_delta_from_bytes(&bytes), Ok(-0x7788_99AB));
+    Fields::set_delta_from_bytes(&mut bytes, -1).unwrap();
+    Fields::set_high_from_bytes(&mut bytes, -2).unwrap();
+    assert_eq!(&bytes[0x4..0xC], [0xFF, 0xFF, 0xFF, 0xFF, 0x44, 0x33, 0xFE, 0xFF]);
+    assert_eq!(Fields::get_high_from_bytes(&bytes), Ok(-2));
+    assert!(Fields::set_high_from_bytes(&mut bytes[..0xB], 0).is_err());
+}
+
+#[test]
+fn enum_macro_fields() {
+    #[derive(ByteStruct)]
+    #[size(0x2)]
+    #[allow(dead_code)]
+    struct Origins {
+        #[offset(0x0)]
+        #[bits(7..11)]
+        #[raw(u8)]
+        origin_game: GameVersion,
+        #[offset(0x0)]
+        #[bits(15..16)]
+        #[raw(u8)]
+        ot_gender: Gender,
+    }
+
+    let mut bytes = [0x05, 0x00];
+    let mut origins = Origins::view_mut(&mut bytes);
+    origins.set_origin_game(GameVersion::LeafGreen).unwrap();
+    origins.set_ot_gender(Gender::Female).unwrap();
+    assert_eq!(origins.origin_game(), Ok(GameVersion::LeafGreen));
+    assert_eq!(origins.ot_gender(), Ok(Gender::Female));
+    assert_eq!(bytes, [0x85, 0x82]);
+
+    let mut origins = Origins::view_mut(&mut bytes);
+    origins.set_origin_game_raw(9).unwrap();
+    assert_eq!(origins.origin_game(), Err(Error::UnknownValue { kind: "GameVersion", value: 9 }));
+    assert_eq!(origins.origin_game_raw(), Ok(9));
+    assert_eq!(Origins::view(&bytes[..1]).ot_gender(), Err(Error::OutOfBounds { offset: 0, size: 2, len: 1 }));
+}
+
+#[test]
+fn array_macro_fields() {
+    struct Fields;
+    impl Fields {
+        byte_parser_proc! { #![size(0x0C)]
+            modifier: i8@0x0,
+            is_egg: bool@0x1,
+            species: u16@0x2#be,
+            #[xor(key: u32)] coins: u16@0x4,
+            name: [u8; 2]@0x6,
+            level: u8@0x8[0..7],
+            #[raw(u8)] origin_game: GameVersion@0x8[7..11],
+            sp_defense_iv: u8@0x8[25..30]
+        }
+    }
+
+    let mut bytes = [0u8; 0x0E];
+    Fields::set_modifier_from_array(&mut bytes, -3);
+    Fields::set_is_egg_from_array(&mut bytes, true);
+    Fields::set_species_from_array(&mut bytes, 0x0115);
+    Fields::set_coins_from_array(&mut bytes, 0x1234_5678, 100);
+    Fields::set_name_from_array(&mut bytes, [0xBB, 0xFF]);
+    Fields::set_level_from_array(&mut bytes, 0xFF);
+    Fields::set_origin_game_from_array(&mut bytes, GameVersion::FireRed);
+    Fields::set_sp_defense_iv_from_array(&mut bytes, 31);
+
+    assert_eq!(bytes, [0xFD, 1, 0x01, 0x15, 0x1C, 0x56, 0xBB, 0xFF, 0x7F, 0x02, 0x00, 0x3E, 0, 0]);
+    assert_eq!(Fields::get_coins_from_bytes(&bytes, 0x1234_5678), Ok(100));
+
+    assert_eq!(Fields::get_modifier_from_array(&bytes), -3);
+    assert!(Fields::get_is_egg_from_array(&bytes));
+    assert_eq!(Fields::get_species_from_array(&bytes), 0x0115);
+    assert_eq!(Fields::get_coins_from_array(&bytes, 0x1234_5678), 100);
+    assert_eq!(Fields::get_name_from_array(&bytes), [0xBB, 0xFF]);
+    assert_eq!(Fields::get_level_from_array(&bytes), 0x7F);
+    assert_eq!(Fields::get_origin_game_from_array(&bytes), Ok(GameVersion::FireRed));
+    assert_eq!(Fields::get_sp_defense_iv_from_array(&bytes), 31);
+
+    Fields::set_origin_game_raw_from_array(&mut bytes, 9);
+    assert_eq!(Fields::get_origin_game_raw_from_array(&bytes), 9);
+    assert_eq!(Fields::get_origin_game_from_array(&bytes), Err(Error::UnknownValue { kind: "GameVersion", value: 9 }));
+    assert_eq!(Fields::get_level_from_array(&bytes), 0x7F);
+}
+
+#[test]
+fn big_endian_bit_fields() {
+    const WORD: usize = 0x0;
+
+    struct Fields;
+    impl Fields {
+        byte_parser_proc! { #![size(0x4)]
+            version: u8@0x0[0..4]#be,
+            flags: u16@0x0[20..32]#be
+        }
+
+        // The offset isn't known by the macro, the bits are still compared for overlaps
+        byte_parser_proc! {
+            language: u8@WORD[4..8]#be,
+            seed: u16@WORD[8..20]#be
+        }
+    }
+
+    // The bits are counted from the highest bit of the big-endian u32 0xA1234567
+    let mut bytes = [0xA1, 0x23, 0x45, 0x67];
+    assert_eq!(Fields::get_version_from_bytes(&bytes), Ok(0xA));
+    assert_eq!(Fields::get_language_from_bytes(&bytes), Ok(0x1));
+    assert_eq!(Fields::get_seed_from_bytes(&bytes), Ok(0x234));
+    assert_eq!(Fields::get_flags_from_bytes(&bytes), Ok(0x567));
+
+    Fields::set_version_from_bytes(&mut bytes, 0x5).unwrap();
+    Fields::set_flags_from_bytes(&mut bytes, 0xFED).unwrap();
+    assert_eq!(bytes, [0x51, 0x23, 0x4F, 0xED]);
+
+    Fields::set_flags_from_array(&mut bytes, 0x123);
+    Fields::set_version_from_array(&mut bytes, 0xC);
+    assert_eq!(bytes, [0xC1, 0x23, 0x41, 0x23]);
+    assert_eq!(Fields::get_version_from_array(&bytes), 0xC);
+    assert_eq!(Fields::get_flags_from_array(&bytes), 0x123);
+    assert_eq!(Fields::get_seed_from_bytes(&bytes), Ok(0x234));
+}
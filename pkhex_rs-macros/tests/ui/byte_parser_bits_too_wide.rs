@@ -0,0 +1,11 @@
+use pkhex_rs_macros::byte_parser_proc;
+
+struct Fields;
+impl Fields {
+    byte_parser_proc! {
+        met_level: u8@0x00[0..12],
+        is_egg: bool@0x04[0..2]
+    }
+}
+
+fn main() {}
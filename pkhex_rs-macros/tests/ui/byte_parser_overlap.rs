@@ -0,0 +1,13 @@
+use pkhex_rs_macros::byte_parser_proc;
+
+struct Fields;
+impl Fields {
+    byte_parser_proc! {
+        trainer_id: u32@0x00,
+        tid: u16@0x00,
+        language: u8@0x04[0..4],
+        version: u8@0x04[3..8]
+    }
+}
+
+fn main() {}
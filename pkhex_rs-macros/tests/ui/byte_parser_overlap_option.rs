@@ -0,0 +1,10 @@
+use pkhex_rs_macros::byte_parser_proc;
+
+struct Fields;
+impl Fields {
+    byte_parser_proc! { #![overlap(sometimes)]
+        checksum: u16@0x00
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use pkhex_rs_macros::byte_parser_proc;
+
+enum Gender {
+    Male,
+    Female
+}
+
+struct Fields;
+impl Fields {
+    byte_parser_proc! {
+        #[raw(char)] gender: Gender@0x00
+    }
+}
+
+fn main() {}
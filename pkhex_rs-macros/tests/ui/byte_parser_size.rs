@@ -0,0 +1,11 @@
+use pkhex_rs_macros::byte_parser_proc;
+
+struct Fields;
+impl Fields {
+    byte_parser_proc! { #![size(0x10)]
+        checksum: u16@0x0E,
+        signature: u32@0x0E+2
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Footer {
+    checksum: u16,
+    #[offset(0x02)]
+    #[endian(middle)]
+    signature: u32,
+    save_index: u32
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Origins {
+    #[offset(0x00)]
+    #[bits(0..9)]
+    met_level: u8
+}
+
+fn main() {}
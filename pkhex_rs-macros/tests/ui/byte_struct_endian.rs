@@ -0,0 +1,10 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Footer {
+    #[offset(0x00)]
+    #[endian(middle)]
+    checksum: u16
+}
+
+fn main() {}
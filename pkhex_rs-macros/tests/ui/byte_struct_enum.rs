@@ -0,0 +1,9 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+enum Gender {
+    Male,
+    Female
+}
+
+fn main() {}
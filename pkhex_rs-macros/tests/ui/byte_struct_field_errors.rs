@@ -0,0 +1,15 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Record {
+    #[offset(0x00)]
+    name: String,
+    #[offset(0x08)]
+    #[bits(0..9)]
+    level: u8,
+    #[offset(0x0C)]
+    #[xor(key)]
+    is_egg: bool
+}
+
+fn main() {}
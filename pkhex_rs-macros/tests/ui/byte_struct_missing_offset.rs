@@ -0,0 +1,10 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Footer {
+    #[offset(0x00)]
+    checksum: u16,
+    signature: u32
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+#[size(0x04)]
+struct Footer {
+    #[offset(0x00)]
+    checksum: u16,
+    #[offset(0x02)]
+    signature: u32
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Footer(#[offset(0x00)] u16);
+
+fn main() {}
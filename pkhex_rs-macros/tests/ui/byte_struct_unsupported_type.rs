@@ -0,0 +1,11 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Footer {
+    #[offset(0x00)]
+    checksum: u16,
+    #[offset(0x02)]
+    name: String
+}
+
+fn main() {}
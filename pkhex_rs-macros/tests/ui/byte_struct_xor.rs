@@ -0,0 +1,13 @@
+use pkhex_rs_macros::ByteStruct;
+
+#[derive(ByteStruct)]
+struct Slot {
+    #[offset(0x00)]
+    item: u16,
+    #[offset(0x02)]
+    #[xor(key)]
+    #[bits(0..10)]
+    quantity: u16
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use pkhex_rs_macros::data_get_set_proc;
+
+struct Fields;
+impl Fields {
+    data_get_set_proc! { time: [u8; 5];
+        get => data.get(0..5)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,321 @@
+use crate::error::Error;
+use crate::utils::SliceUtils;
+
+/// Declares the readers of integers in the given endianess, built on [`ByteReader::read_array`]
+macro_rules! cursor_reads {
+    ($($endianess:ident => $($type:ty),*);*) => {
+        paste::paste! {
+            $($(
+                pub fn [<read_ $type _ $endianess>](&mut self) -> Result<$type, Error> {
+                    Ok($type::[<from_ $endianess _bytes>](self.read_array()?))
+                }
+            )*)*
+        }
+    };
+}
+
+/// Declares the writers of integers in the given endianess, built on [`ByteWriter::write_array`]
+macro_rules! cursor_writes {
+    ($($endianess:ident => $($type:ty),*);*) => {
+        paste::paste! {
+            $($(
+                pub fn [<write_ $type _ $endianess>](&mut self, value: $type) -> Result<(), Error> {
+                    self.write_array(value.[<to_ $endianess _bytes>]())
+                }
+            )*)*
+        }
+    };
+}
+
+/// Moves an error of a view to the positions of the buffer the first view was made from
+fn rebase(error: Error, base: usize) -> Error {
+    match error {
+        Error::OutOfBounds { offset, size, len } => Error::OutOfBounds {
+            offset: base.saturating_add(offset),
+            size,
+            len: base + len,
+        },
+        error => error,
+    }
+}
+
+/// Checks that the position is within the `len` bytes of a view, the end included
+fn check_position(position: usize, len: usize, base: usize) -> Result<usize, Error> {
+    if position <= len {
+        Ok(position)
+    } else {
+        Err(rebase(Error::OutOfBounds { offset: position, size: 0, len }, base))
+    }
+}
+
+/// Reads values one after the other, keeping track of the position
+///
+/// Sub-readers count their positions from their own start, but their errors
+/// report the offsets in the buffer the first reader was made from
+///
+/// # Examples
+/// ```
+/// # use pkhex_rs::cursor::ByteReader;
+/// # use pkhex_rs::error::Error;
+/// let bytes = [0x01, 0x2A, 0x00, 0x03, 0x04, 0x05];
+/// let mut reader = ByteReader::new(&bytes);
+///
+/// reader.skip(1)?;
+/// let number = reader.read_u16_le()?;
+/// let mut rest = reader.sub_reader(3)?;
+///
+/// # assert_eq!(number, 42);
+/// # assert_eq!(rest.read_array::<2>()?, [0x03, 0x04]);
+/// # assert_eq!(rest.read_u16_le(), Err(Error::OutOfBounds { offset: 5, size: 2, len: 6 }));
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    /// Offset of the reader in the buffer of the first reader
+    base: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        ByteReader { data, position: 0, base: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Amount of bytes left after the position
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    /// Moves to the position, which can be the end but not past it
+    pub fn seek(&mut self, position: usize) -> Result<(), Error> {
+        self.position = check_position(position, self.data.len(), self.base)?;
+        Ok(())
+    }
+
+    pub fn skip(&mut self, count: usize) -> Result<(), Error> {
+        self.seek(self.position.saturating_add(count))
+    }
+
+    /// Returns the next `count` bytes and moves past them
+    pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        let data: &'a [u8] = self.data;
+        let bytes = data.get_offset(self.position, count).map_err(|error| rebase(error, self.base))?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    /// Takes the next `size` bytes as a reader of their own, starting at their first byte
+    pub fn sub_reader(&mut self, size: usize) -> Result<ByteReader<'a>, Error> {
+        let base = self.base + self.position;
+        let data = self.read_bytes(size)?;
+        Ok(ByteReader { data, position: 0, base })
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8, Error> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    /// Any byte other than `0` is read as `true`
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    cursor_reads! {
+        le => u16, i16, u32, i32, u64, i64;
+        be => u16, i16, u32, i32, u64, i64
+    }
+}
+
+/// Writes values one after the other, keeping track of the position
+///
+/// Like [`ByteReader`], sub-writers count their positions from their own start
+/// and report the offsets of the first writer in their errors
+///
+/// # Examples
+/// ```
+/// # use pkhex_rs::cursor::ByteWriter;
+/// # use pkhex_rs::error::Error;
+/// let mut bytes = [0u8; 6];
+/// let mut writer = ByteWriter::new(&mut bytes);
+///
+/// writer.skip(1)?;
+/// writer.write_u16_be(42)?;
+/// writer.write_array([0x03, 0x04])?;
+///
+/// # assert_eq!(writer.write_u16_le(0), Err(Error::OutOfBounds { offset: 5, size: 2, len: 6 }));
+/// # assert_eq!(bytes, [0x00, 0x00, 0x2A, 0x03, 0x04, 0x00]);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ByteWriter<'a> {
+    data: &'a mut [u8],
+    position: usize,
+    /// Offset of the writer in the buffer of the first writer
+    base: usize,
+}
+
+impl<'a> ByteWriter<'a> {
+    pub fn new(data: &'a mut [u8]) -> Self {
+        ByteWriter { data, position: 0, base: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Amount of bytes left after the position
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    /// Moves to the position, which can be the end but not past it
+    pub fn seek(&mut self, position: usize) -> Result<(), Error> {
+        self.position = check_position(position, self.data.len(), self.base)?;
+        Ok(())
+    }
+
+    pub fn skip(&mut self, count: usize) -> Result<(), Error> {
+        self.seek(self.position.saturating_add(count))
+    }
+
+    /// Copies the bytes at the position and moves past them,
+    /// nothing is written if they don't fit
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.data.write_into(bytes, self.position).map_err(|error| rebase(error, self.base))?;
+        self.position += bytes.len();
+        Ok(())
+    }
+
+    pub fn write_array<const N: usize>(&mut self, value: [u8; N]) -> Result<(), Error> {
+        self.write_bytes(&value)
+    }
+
+    /// Takes the next `size` bytes as a writer of their own, starting at their first byte
+    pub fn sub_writer(&mut self, size: usize) -> Result<ByteWriter<'_>, Error> {
+        let (position, base) = (self.position, self.base + self.position);
+        let data = self.data.get_mutable_offset(position, size).map_err(|error| rebase(error, self.base))?;
+        self.position += size;
+        Ok(ByteWriter { data, position: 0, base })
+    }
+
+    pub fn write_u8(&mut self, value: u8) -> Result<(), Error> {
+        self.write_bytes(&[value])
+    }
+
+    pub fn write_i8(&mut self, value: i8) -> Result<(), Error> {
+        self.write_u8(value as u8)
+    }
+
+    pub fn write_bool(&mut self, value: bool) -> Result<(), Error> {
+        self.write_u8(value as u8)
+    }
+
+    cursor_writes! {
+        le => u16, i16, u32, i32, u64, i64;
+        be => u16, i16, u32, i32, u64, i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    use super::{ByteReader, ByteWriter};
+
+    #[test]
+    fn sequential_reads() {
+        let bytes = [0xFE, 0x34, 0x12, 0x00, 0x00, 0x00, 0x2A, 0x01, 0xAB, 0xCD];
+        let mut reader = ByteReader::new(&bytes);
+
+        assert_eq!(reader.read_i8(), Ok(-2));
+        assert_eq!(reader.read_u16_le(), Ok(0x1234));
+        assert_eq!(reader.read_u32_be(), Ok(0x2A));
+        assert!(reader.read_bool().unwrap());
+        assert_eq!(reader.position(), 8);
+        assert_eq!(reader.remaining(), 2);
+        assert_eq!(reader.read_u32_le(), Err(Error::OutOfBounds { offset: 8, size: 4, len: 10 }));
+        assert_eq!(reader.position(), 8, "A failed read doesn't move the reader");
+
+        reader.seek(1).unwrap();
+        assert_eq!(reader.read_u16_be(), Ok(0x3412));
+        reader.skip(4).unwrap();
+        assert_eq!(reader.read_array(), Ok([0x01, 0xAB, 0xCD]));
+        assert_eq!(reader.remaining(), 0);
+
+        assert_eq!(reader.seek(11), Err(Error::OutOfBounds { offset: 11, size: 0, len: 10 }));
+        assert_eq!(reader.skip(usize::MAX), Err(Error::OutOfBounds { offset: usize::MAX, size: 0, len: 10 }));
+        assert!(reader.seek(10).is_ok());
+    }
+
+    #[test]
+    fn sub_readers_report_absolute_offsets() {
+        let bytes = [0u8, 1, 2, 3, 4, 5, 6, 7];
+        let mut reader = ByteReader::new(&bytes);
+        reader.skip(2).unwrap();
+
+        let mut record = reader.sub_reader(4).unwrap();
+        assert_eq!(reader.position(), 6);
+        assert_eq!(record.len(), 4);
+        assert_eq!(record.read_u8(), Ok(2));
+
+        let mut field = record.sub_reader(2).unwrap();
+        assert_eq!(field.read_u16_le(), Ok(0x0403));
+        assert_eq!(field.read_u8(), Err(Error::OutOfBounds { offset: 5, size: 1, len: 5 }));
+        assert_eq!(record.seek(5), Err(Error::OutOfBounds { offset: 7, size: 0, len: 6 }));
+        assert_eq!(reader.sub_reader(3).err(), Some(Error::OutOfBounds { offset: 6, size: 3, len: 8 }));
+    }
+
+    #[test]
+    fn sequential_writes() {
+        let mut bytes = [0u8; 12];
+        let mut writer = ByteWriter::new(&mut bytes);
+
+        writer.write_u16_le(0x1234).unwrap();
+        writer.write_i32_be(-2).unwrap();
+        writer.write_bool(true).unwrap();
+        writer.skip(1).unwrap();
+
+        let mut record = writer.sub_writer(3).unwrap();
+        record.write_i8(-1).unwrap();
+        record.write_u16_be(0xABCD).unwrap();
+        assert_eq!(record.write_u8(0), Err(Error::OutOfBounds { offset: 11, size: 1, len: 11 }));
+
+        assert_eq!(writer.position(), 11);
+        assert_eq!(writer.write_u16_le(0), Err(Error::OutOfBounds { offset: 11, size: 2, len: 12 }));
+        writer.seek(12).unwrap();
+        assert_eq!(writer.remaining(), 0);
+
+        assert_eq!(bytes, [0x34, 0x12, 0xFF, 0xFF, 0xFF, 0xFE, 0x01, 0x00, 0xFF, 0xAB, 0xCD, 0x00]);
+    }
+}
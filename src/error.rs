@@ -0,0 +1,95 @@
+use std::fmt::{Display, Formatter, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Reading or writing `size` bytes at `offset` goes past the `len` bytes available
+    OutOfBounds { offset: usize, size: usize, len: usize },
+    /// A section footer doesn't have the expected magic value
+    BadSignature { section_id: u16, signature: u32 },
+    /// The checksum stored in a section footer doesn't match its data
+    BadChecksum { section_id: u16, stored: u16, computed: u16 },
+    /// The game version couldn't be determined from the save
+    UnknownGame,
+    /// A section ID outside of `0..14`, or repeated in the same slot
+    InvalidSectionId(u16),
+    /// A section of a slot has another save index than the first one, the slot was only partly written
+    MismatchedSaveIndex { section_id: u16, expected: u32, found: u32 },
+    /// An index past the `count` elements of a list, like a box or a party slot
+    InvalidIndex { index: usize, count: usize },
+    /// A Pokémon record that isn't 80 or 100 bytes long
+    InvalidPokemonSize(usize),
+    /// A value above the most the game allows for it
+    ValueOutOfRange { value: u32, max: u32 },
+    /// A stored value that doesn't match any variant of the enum named `kind`
+    UnknownValue { kind: &'static str, value: u32 },
+    /// Every slot of a bag pocket already holds an item
+    PocketFull { capacity: usize },
+    /// The item is not in the bag pocket
+    ItemNotFound(u16),
+    Text(TextError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Error::OutOfBounds { offset, size, len } => write!(
+                f,
+                "Tried to access {} bytes at offset {:#06X}, but only {:#06X} bytes are available",
+                size, offset, len
+            ),
+            Error::BadSignature { section_id, signature } => {
+                write!(f, "The section {} has an invalid signature: {:#010X}", section_id, signature)
+            }
+            Error::BadChecksum { section_id, stored, computed } => write!(
+                f,
+                "The section {} has the checksum {:#06X}, but its data sums to {:#06X}",
+                section_id, stored, computed
+            ),
+            Error::UnknownGame => write!(f, "The game version could not be determined"),
+            Error::InvalidSectionId(id) => write!(f, "The section ID {} is not valid", id),
+            Error::MismatchedSaveIndex { section_id, expected, found } => write!(
+                f,
+                "The section {} has the save index {}, but the rest of its slot has {}",
+                section_id, found, expected
+            ),
+            Error::InvalidIndex { index, count } => {
+                write!(f, "The index {} is out of range, there are only {} elements", index, count)
+            }
+            Error::InvalidPokemonSize(size) => {
+                write!(f, "A Pokémon record has 80 or 100 bytes, but {} were given", size)
+            }
+            Error::ValueOutOfRange { value, max } => write!(f, "The value {} is above the maximum of {}", value, max),
+            Error::UnknownValue { kind, value } => write!(f, "The value {} is not a known {}", value, kind),
+            Error::PocketFull { capacity } => write!(f, "The pocket is full, it only has {} slots", capacity),
+            Error::ItemNotFound(item) => write!(f, "The item {} is not in the pocket", item),
+            Error::Text(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<TextError> for Error {
+    fn from(error: TextError) -> Self {
+        Error::Text(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextError {
+    /// The character has no value in the character table
+    UnencodableCharacter(char),
+    /// The text does not fit in the buffer
+    TooLong { max: usize },
+}
+
+impl Display for TextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            TextError::UnencodableCharacter(c) => write!(f, "The character {:?} can't be encoded", c),
+            TextError::TooLong { max } => write!(f, "The text is longer than {} characters", max),
+        }
+    }
+}
+
+impl std::error::Error for TextError {}
@@ -1,2 +1,5 @@
+pub mod error;
 pub mod utils;
+pub mod cursor;
 pub mod save;
+pub mod pkm;
\ No newline at end of file
@@ -0,0 +1,713 @@
+use pkhex_rs_macros::byte_parser_proc;
+
+use crate::error::Error;
+use crate::save::gen3::gen3_save::TrainerId;
+use crate::save::gen3::gen3_text::{self, Gen3Charset};
+use crate::utils::SliceUtils;
+
+/// Size of a Pokémon stored in the PC
+pub const SIZE_STORED: usize = 80;
+/// Size of a Pokémon in the party, the stored data plus the battle stats
+pub const SIZE_PARTY: usize = 100;
+
+const DATA_OFFSET: usize = 0x20;
+const DATA_SIZE: usize = 48;
+const BLOCK_SIZE: usize = 12;
+
+/// Order in which the substructures are stored for each `PID % 24`,
+/// with 0 being Growth, 1 Attacks, 2 EVs & Condition and 3 Miscellaneous
+const BLOCK_ORDERS: [[usize; 4]; 24] = [
+    [0, 1, 2, 3], [0, 1, 3, 2], [0, 2, 1, 3], [0, 2, 3, 1], [0, 3, 1, 2], [0, 3, 2, 1],
+    [1, 0, 2, 3], [1, 0, 3, 2], [1, 2, 0, 3], [1, 2, 3, 0], [1, 3, 0, 2], [1, 3, 2, 0],
+    [2, 0, 1, 3], [2, 0, 3, 1], [2, 1, 0, 3], [2, 1, 3, 0], [2, 3, 0, 1], [2, 3, 1, 0],
+    [3, 0, 1, 2], [3, 0, 2, 1], [3, 1, 0, 2], [3, 1, 2, 0], [3, 2, 0, 1], [3, 2, 1, 0],
+];
+
+/// Language value used by the Japanese games, every other language uses the international characters
+const LANGUAGE_JAPANESE: u8 = 1;
+
+/// Conditions that make the game show a Pokémon as a Bad Egg
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadEggReason {
+    /// The stored checksum doesn't match the decrypted data
+    ChecksumMismatch { stored: u16, computed: u16 },
+    /// The game already flagged the Pokémon as a Bad Egg
+    BadEggFlag,
+}
+
+/// Games a Gen 3 Pokémon can come from, with the version IDs stored in its data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameVersion {
+    Sapphire = 1,
+    Ruby = 2,
+    Emerald = 3,
+    FireRed = 4,
+    LeafGreen = 5,
+    /// Both Colosseum and XD use the same ID
+    ColosseumXd = 15,
+}
+
+impl TryFrom<u8> for GameVersion {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Error> {
+        match value {
+            1 => Ok(GameVersion::Sapphire),
+            2 => Ok(GameVersion::Ruby),
+            3 => Ok(GameVersion::Emerald),
+            4 => Ok(GameVersion::FireRed),
+            5 => Ok(GameVersion::LeafGreen),
+            15 => Ok(GameVersion::ColosseumXd),
+            _ => Err(Error::UnknownValue { kind: "GameVersion", value: value as u32 }),
+        }
+    }
+}
+
+impl From<GameVersion> for u8 {
+    fn from(version: GameVersion) -> u8 {
+        version as u8
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    Male = 0,
+    Female = 1,
+}
+
+impl TryFrom<u8> for Gender {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Error> {
+        match value {
+            0 => Ok(Gender::Male),
+            1 => Ok(Gender::Female),
+            _ => Err(Error::UnknownValue { kind: "Gender", value: value as u32 }),
+        }
+    }
+}
+
+impl From<Gender> for u8 {
+    fn from(gender: Gender) -> u8 {
+        gender as u8
+    }
+}
+
+/// A Gen 3 Pokémon
+///
+/// The data is kept decrypted, with the substructures in the Growth, Attacks,
+/// EVs & Condition, Miscellaneous order starting at `0x20`, so every field
+/// lives at a fixed offset regardless of the PID
+///
+/// Stats are always in the order the game stores them: HP, Attack, Defense,
+/// Speed, Special Attack and Special Defense
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PK3 {
+    data: [u8; SIZE_PARTY],
+    party: bool,
+    /// The data as loaded when the stored checksum was already wrong, kept to write
+    /// the record back unchanged, so an untouched Bad Egg doesn't turn into a valid Pokémon
+    loaded_bad_egg: Option<Box<[u8; SIZE_PARTY]>>,
+}
+
+impl PK3 {
+    /// Decrypts a Pokémon record as found in the save, 80 bytes for the PC
+    /// and 100 bytes for the party
+    ///
+    /// Fails with [`Error::InvalidPokemonSize`] if the record has any other size
+    pub fn from_encrypted_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let party = match bytes.len() {
+            SIZE_STORED => false,
+            SIZE_PARTY => true,
+            size => return Err(Error::InvalidPokemonSize(size)),
+        };
+
+        let mut data = [0u8; SIZE_PARTY];
+        data[..bytes.len()].copy_from_slice(bytes);
+
+        let pid = bytes.get_u32_le_offset(0x00)?;
+        let ot_id = bytes.get_u32_le_offset(0x04)?;
+        let substructures = &mut data[DATA_OFFSET..DATA_OFFSET + DATA_SIZE];
+        xor_data(substructures, pid ^ ot_id);
+        reorder_blocks(substructures, pid, false);
+
+        let mut pk3 = PK3 { data, party, loaded_bad_egg: None };
+        if pk3.checksum() != pk3.compute_checksum() {
+            pk3.loaded_bad_egg = Some(Box::new(pk3.data));
+        }
+
+        Ok(pk3)
+    }
+
+    /// Encrypts the Pokémon back into the format used by the save,
+    /// with a freshly computed checksum and the substructures shuffled by the PID
+    ///
+    /// Records whose checksum was already wrong when loaded keep their stored checksum
+    /// as long as they aren't edited, so a save written back doesn't fix its Bad Eggs,
+    /// any edit gets them a fresh checksum like the other records
+    pub fn to_encrypted_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.decrypted_bytes().to_vec();
+        if self.loaded_bad_egg.as_deref() != Some(&self.data) {
+            bytes[0x1C..0x1E].copy_from_slice(&self.compute_checksum().to_le_bytes());
+        }
+
+        let pid = self.pid();
+        let substructures = &mut bytes[DATA_OFFSET..DATA_OFFSET + DATA_SIZE];
+        reorder_blocks(substructures, pid, true);
+        xor_data(substructures, pid ^ self.ot_id());
+
+        bytes
+    }
+
+    /// The sum of the decrypted substructures as 16bit words
+    pub fn compute_checksum(&self) -> u16 {
+        self.data[DATA_OFFSET..DATA_OFFSET + DATA_SIZE]
+            .chunks_exact(2)
+            .fold(0u16, |sum, word| sum.wrapping_add(u16::from_le_bytes([word[0], word[1]])))
+    }
+
+    /// Stores the checksum of the current data, to be called after editing
+    pub fn refresh_checksum(&mut self) {
+        let checksum = self.compute_checksum();
+        PK3::set_checksum_from_array(&mut self.data, checksum)
+    }
+
+    /// Checks the Pokémon the same way the game does when loading it,
+    /// returning why it would be shown as a Bad Egg
+    pub fn validate(&self) -> Result<(), BadEggReason> {
+        if self.is_bad_egg_flag() {
+            return Err(BadEggReason::BadEggFlag);
+        }
+
+        let stored = self.checksum();
+        let computed = self.compute_checksum();
+        if stored != computed {
+            return Err(BadEggReason::ChecksumMismatch { stored, computed });
+        }
+
+        Ok(())
+    }
+
+    pub fn is_bad_egg(&self) -> bool {
+        self.validate().is_err()
+    }
+
+    /// Whether the record has the party stats
+    pub fn is_party(&self) -> bool {
+        self.party
+    }
+
+    /// The decrypted data in the fixed substructure order, 80 or 100 bytes long
+    pub fn decrypted_bytes(&self) -> &[u8] {
+        if self.party {
+            &self.data
+        } else {
+            &self.data[..SIZE_STORED]
+        }
+    }
+
+    /// Characters of the nickname and OT name, picked by the language
+    pub fn charset(&self) -> Gen3Charset {
+        if self.language() == LANGUAGE_JAPANESE {
+            Gen3Charset::Japanese
+        } else {
+            Gen3Charset::International
+        }
+    }
+
+    // Header
+
+    byte_parser_proc! { #![size(DATA_OFFSET)]
+        pid: u32@0x00,
+        ot_id: u32@0x04,
+        language: u8@0x12,
+        is_bad_egg_flag: bool@0x13[0..1],
+        has_species_flag: bool@0x13[1..2],
+        markings: u8@0x1B,
+        checksum: u16@0x1C
+    }
+
+    pub fn pid(&self) -> u32 {
+        PK3::get_pid_from_array(&self.data)
+    }
+
+    /// The full 32bit ID of the original trainer, visible ID in the lower half
+    pub fn ot_id(&self) -> u32 {
+        PK3::get_ot_id_from_array(&self.data)
+    }
+
+    pub fn trainer_id(&self) -> TrainerId {
+        TrainerId::new(self.ot_id())
+    }
+
+    /// See [`TrainerId::shiny_xor`]
+    pub fn shiny_xor(&self) -> u16 {
+        self.trainer_id().shiny_xor(self.pid())
+    }
+
+    pub fn is_shiny(&self) -> bool {
+        self.trainer_id().is_shiny(self.pid())
+    }
+
+    pub fn nickname_bytes(&self) -> &[u8] {
+        &self.data[0x08..0x12]
+    }
+
+    pub fn nickname(&self) -> String {
+        gen3_text::decode(self.nickname_bytes(), self.charset())
+    }
+
+    pub fn language(&self) -> u8 {
+        PK3::get_language_from_array(&self.data)
+    }
+
+    /// Set by the game when the checksum didn't match
+    pub fn is_bad_egg_flag(&self) -> bool {
+        PK3::get_is_bad_egg_flag_from_array(&self.data)
+    }
+
+    pub fn has_species_flag(&self) -> bool {
+        PK3::get_has_species_flag_from_array(&self.data)
+    }
+
+    pub fn ot_name_bytes(&self) -> &[u8] {
+        &self.data[0x14..0x1B]
+    }
+
+    pub fn ot_name(&self) -> String {
+        gen3_text::decode(self.ot_name_bytes(), self.charset())
+    }
+
+    pub fn markings(&self) -> u8 {
+        PK3::get_markings_from_array(&self.data)
+    }
+
+    /// The checksum stored in the record
+    pub fn checksum(&self) -> u16 {
+        PK3::get_checksum_from_array(&self.data)
+    }
+
+    // Growth
+
+    byte_parser_proc! { #![size(DATA_OFFSET + BLOCK_SIZE)]
+        species: u16@0x20,
+        held_item: u16@0x22,
+        experience: u32@0x24,
+        pp_ups: u8@0x28,
+        friendship: u8@0x29
+    }
+
+    pub fn species(&self) -> u16 {
+        PK3::get_species_from_array(&self.data)
+    }
+
+    pub fn set_species(&mut self, species: u16) {
+        PK3::set_species_from_array(&mut self.data, species)
+    }
+
+    pub fn held_item(&self) -> u16 {
+        PK3::get_held_item_from_array(&self.data)
+    }
+
+    pub fn set_held_item(&mut self, item: u16) {
+        PK3::set_held_item_from_array(&mut self.data, item)
+    }
+
+    pub fn experience(&self) -> u32 {
+        PK3::get_experience_from_array(&self.data)
+    }
+
+    pub fn set_experience(&mut self, experience: u32) {
+        PK3::set_experience_from_array(&mut self.data, experience)
+    }
+
+    /// The PP Ups applied to each move, 2 bits per move
+    pub fn pp_ups(&self) -> [u8; 4] {
+        let pp_ups = PK3::get_pp_ups_from_array(&self.data);
+        [0, 2, 4, 6].map(|shift| (pp_ups >> shift) & 3)
+    }
+
+    pub fn friendship(&self) -> u8 {
+        PK3::get_friendship_from_array(&self.data)
+    }
+
+    pub fn set_friendship(&mut self, friendship: u8) {
+        PK3::set_friendship_from_array(&mut self.data, friendship)
+    }
+
+    // Attacks
+
+    byte_parser_proc! { #![size(DATA_OFFSET + BLOCK_SIZE * 2)]
+        move_1: u16@0x2C,
+        move_2: u16@0x2E,
+        move_3: u16@0x30,
+        move_4: u16@0x32,
+        pp: [u8; 4]@0x34
+    }
+
+    pub fn moves(&self) -> [u16; 4] {
+        [
+            PK3::get_move_1_from_array(&self.data),
+            PK3::get_move_2_from_array(&self.data),
+            PK3::get_move_3_from_array(&self.data),
+            PK3::get_move_4_from_array(&self.data),
+        ]
+    }
+
+    pub fn set_moves(&mut self, moves: [u16; 4]) {
+        let [move_1, move_2, move_3, move_4] = moves;
+        PK3::set_move_1_from_array(&mut self.data, move_1);
+        PK3::set_move_2_from_array(&mut self.data, move_2);
+        PK3::set_move_3_from_array(&mut self.data, move_3);
+        PK3::set_move_4_from_array(&mut self.data, move_4);
+    }
+
+    pub fn pp(&self) -> [u8; 4] {
+        PK3::get_pp_from_array(&self.data)
+    }
+
+    pub fn set_pp(&mut self, pp: [u8; 4]) {
+        PK3::set_pp_from_array(&mut self.data, pp)
+    }
+
+    // EVs & Condition
+
+    byte_parser_proc! { #![size(DATA_OFFSET + BLOCK_SIZE * 3)]
+        evs: [u8; 6]@0x38,
+        contest_stats: [u8; 6]@0x3E
+    }
+
+    pub fn evs(&self) -> [u8; 6] {
+        PK3::get_evs_from_array(&self.data)
+    }
+
+    pub fn set_evs(&mut self, evs: [u8; 6]) {
+        PK3::set_evs_from_array(&mut self.data, evs)
+    }
+
+    /// Cool, Beauty, Cute, Smart, Tough and Sheen
+    pub fn contest_stats(&self) -> [u8; 6] {
+        PK3::get_contest_stats_from_array(&self.data)
+    }
+
+    // Miscellaneous
+
+    byte_parser_proc! { #![size(SIZE_STORED)]
+        pokerus: u8@0x44,
+        met_location: u8@0x45,
+        met_level: u8@0x46[0..7],
+        #[raw(u8)] origin_game: GameVersion@0x46[7..11],
+        ball: u8@0x46[11..15],
+        #[raw(u8)] ot_gender: Gender@0x46[15..16],
+        hp_iv: u8@0x48[0..5],
+        attack_iv: u8@0x48[5..10],
+        defense_iv: u8@0x48[10..15],
+        speed_iv: u8@0x48[15..20],
+        sp_attack_iv: u8@0x48[20..25],
+        sp_defense_iv: u8@0x48[25..30],
+        is_egg: bool@0x48[30..31],
+        ability_bit: bool@0x48[31..32],
+        ribbons: u32@0x4C
+    }
+
+    pub fn pokerus(&self) -> u8 {
+        PK3::get_pokerus_from_array(&self.data)
+    }
+
+    pub fn met_location(&self) -> u8 {
+        PK3::get_met_location_from_array(&self.data)
+    }
+
+    pub fn met_level(&self) -> u8 {
+        PK3::get_met_level_from_array(&self.data)
+    }
+
+    /// Game of origin, fails with [`Error::UnknownValue`] for the IDs
+    /// not used by the Gen 3 games, which [`PK3::origin_game_raw`] still reads
+    pub fn origin_game(&self) -> Result<GameVersion, Error> {
+        PK3::get_origin_game_from_array(&self.data)
+    }
+
+    pub fn origin_game_raw(&self) -> u8 {
+        PK3::get_origin_game_raw_from_array(&self.data)
+    }
+
+    pub fn set_origin_game(&mut self, version: GameVersion) {
+        PK3::set_origin_game_from_array(&mut self.data, version)
+    }
+
+    /// Sets the game of origin from its ID, truncated to 4 bits
+    pub fn set_origin_game_raw(&mut self, version: u8) {
+        PK3::set_origin_game_raw_from_array(&mut self.data, version)
+    }
+
+    pub fn ball(&self) -> u8 {
+        PK3::get_ball_from_array(&self.data)
+    }
+
+    /// The single bit holding it always maps to a gender
+    pub fn ot_gender(&self) -> Gender {
+        match PK3::get_ot_gender_raw_from_array(&self.data) {
+            0 => Gender::Male,
+            _ => Gender::Female,
+        }
+    }
+
+    pub fn set_ot_gender(&mut self, gender: Gender) {
+        PK3::set_ot_gender_from_array(&mut self.data, gender)
+    }
+
+    pub fn ivs(&self) -> [u8; 6] {
+        [
+            PK3::get_hp_iv_from_array(&self.data),
+            PK3::get_attack_iv_from_array(&self.data),
+            PK3::get_defense_iv_from_array(&self.data),
+            PK3::get_speed_iv_from_array(&self.data),
+            PK3::get_sp_attack_iv_from_array(&self.data),
+            PK3::get_sp_defense_iv_from_array(&self.data),
+        ]
+    }
+
+    /// Sets the IVs, values above 31 are truncated
+    pub fn set_ivs(&mut self, ivs: [u8; 6]) {
+        let [hp, attack, defense, speed, sp_attack, sp_defense] = ivs;
+        PK3::set_hp_iv_from_array(&mut self.data, hp);
+        PK3::set_attack_iv_from_array(&mut self.data, attack);
+        PK3::set_defense_iv_from_array(&mut self.data, defense);
+        PK3::set_speed_iv_from_array(&mut self.data, speed);
+        PK3::set_sp_attack_iv_from_array(&mut self.data, sp_attack);
+        PK3::set_sp_defense_iv_from_array(&mut self.data, sp_defense);
+    }
+
+    pub fn is_egg(&self) -> bool {
+        PK3::get_is_egg_from_array(&self.data)
+    }
+
+    /// Which of the two abilities of the species is used
+    pub fn ability_bit(&self) -> bool {
+        PK3::get_ability_bit_from_array(&self.data)
+    }
+
+    /// The ribbon bits, with the contest ranks in the lower bits and
+    /// the fateful encounter flag in the highest one
+    pub fn ribbons(&self) -> u32 {
+        PK3::get_ribbons_from_array(&self.data)
+    }
+
+    pub fn set_ribbons(&mut self, ribbons: u32) {
+        PK3::set_ribbons_from_array(&mut self.data, ribbons)
+    }
+}
+
+/// Moves the substructures between the order given by the PID and
+/// the fixed Growth, Attacks, EVs & Condition, Miscellaneous order
+fn reorder_blocks(data: &mut [u8], pid: u32, shuffle: bool) {
+    let original = data.to_vec();
+
+    for (stored_position, &block) in BLOCK_ORDERS[(pid % 24) as usize].iter().enumerate() {
+        let (from, to) = if shuffle { (block, stored_position) } else { (stored_position, block) };
+        data[to * BLOCK_SIZE..(to + 1) * BLOCK_SIZE].copy_from_slice(&original[from * BLOCK_SIZE..(from + 1) * BLOCK_SIZE]);
+    }
+}
+
+/// Encryption and decryption are the same XOR of every 32bit word with the key
+fn xor_data(data: &mut [u8], key: u32) {
+    for word in data.chunks_exact_mut(4) {
+        let value = u32::from_le_bytes([word[0], word[1], word[2], word[3]]) ^ key;
+        word.copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::save::gen3::gen3_save::TrainerId;
+
+    use super::{xor_data, BadEggReason, GameVersion, Gender, BLOCK_ORDERS, PK3, SIZE_PARTY, SIZE_STORED};
+
+    /// Builds an encrypted record by hand from the substructures in order
+    fn encrypt(pid: u32, ot_id: u32, blocks: [[u8; 12]; 4], party: bool) -> Vec<u8> {
+        let mut bytes = vec![0u8; if party { SIZE_PARTY } else { SIZE_STORED }];
+        bytes[0x00..0x04].copy_from_slice(&pid.to_le_bytes());
+        bytes[0x04..0x08].copy_from_slice(&ot_id.to_le_bytes());
+        bytes[0x08..0x0D].copy_from_slice(&[0xBB, 0xBC, 0xBD, 0xFF, 0x12]);
+        bytes[0x12] = 2;
+
+        for (position, &block) in BLOCK_ORDERS[(pid % 24) as usize].iter().enumerate() {
+            bytes[0x20 + position * 12..0x20 + (position + 1) * 12].copy_from_slice(&blocks[block]);
+        }
+        xor_data(&mut bytes[0x20..0x50], pid ^ ot_id);
+
+        bytes
+    }
+
+    fn sample_blocks() -> [[u8; 12]; 4] {
+        let growth = [0x19, 0x01, 0x0D, 0x00, 0x40, 0x42, 0x0F, 0x00, 0b11_10_01_00, 70, 0, 0];
+        let attacks = [0x55, 0x00, 0x62, 0x00, 0x00, 0x00, 0x00, 0x00, 15, 30, 0, 0];
+        let evs = [1, 2, 3, 4, 5, 6, 10, 20, 30, 40, 50, 255];
+        let mut misc = [0u8; 12];
+        misc[0] = 0x01;
+        misc[1] = 0x10;
+        // Met at level 5 in Emerald (3) in a Poké Ball (4) by a female OT
+        misc[2..4].copy_from_slice(&(5u16 | (3 << 7) | (4 << 11) | (1 << 15)).to_le_bytes());
+        // IVs 31, 0, 1, 2, 3, 30 with the ability bit set
+        let ivs: u32 = 31 | (1 << 10) | (2 << 15) | (3 << 20) | (30 << 25) | (1 << 31);
+        misc[4..8].copy_from_slice(&ivs.to_le_bytes());
+        misc[8..12].copy_from_slice(&0x8000_0001u32.to_le_bytes());
+
+        [growth, attacks, evs, misc]
+    }
+
+    #[test]
+    fn decrypts_every_block_order() {
+        for order in 0..24u32 {
+            let pid = 0x1357_9B00 + order;
+            let bytes = encrypt(pid, 0xDEAD_BEEF, sample_blocks(), false);
+            let pk3 = PK3::from_encrypted_bytes(&bytes).unwrap();
+
+            assert_eq!(pk3.pid(), pid);
+            assert_eq!(pk3.species(), 0x119);
+            assert_eq!(pk3.held_item(), 0x0D);
+            assert_eq!(pk3.experience(), 1_000_000);
+            assert_eq!(pk3.pp_ups(), [0, 1, 2, 3]);
+            assert_eq!(pk3.friendship(), 70);
+            assert_eq!(pk3.moves(), [0x55, 0x62, 0, 0]);
+            assert_eq!(pk3.pp(), [15, 30, 0, 0]);
+            assert_eq!(pk3.evs(), [1, 2, 3, 4, 5, 6]);
+            assert_eq!(pk3.contest_stats(), [10, 20, 30, 40, 50, 255]);
+        }
+    }
+
+    #[test]
+    fn misc_fields() {
+        let bytes = encrypt(0x1234_5678, 0x0001_0002, sample_blocks(), true);
+        let pk3 = PK3::from_encrypted_bytes(&bytes).unwrap();
+
+        assert!(pk3.is_party());
+        assert_eq!(pk3.ot_id(), 0x0001_0002);
+        assert_eq!(pk3.nickname(), "ABC");
+        assert_eq!(pk3.pokerus(), 0x01);
+        assert_eq!(pk3.met_location(), 0x10);
+        assert_eq!(pk3.met_level(), 5);
+        assert_eq!(pk3.origin_game(), Ok(GameVersion::Emerald));
+        assert_eq!(pk3.origin_game_raw(), 3);
+        assert_eq!(pk3.ball(), 4);
+        assert_eq!(pk3.ot_gender(), Gender::Female);
+        assert_eq!(pk3.ivs(), [31, 0, 1, 2, 3, 30]);
+        assert!(!pk3.is_egg());
+        assert!(pk3.ability_bit());
+        assert_eq!(pk3.ribbons(), 0x8000_0001);
+    }
+
+    #[test]
+    fn shininess() {
+        let id = TrainerId::from_parts(12345, 54321);
+        let shiny_pid = ((12345u32 ^ 54321) << 16) | 0x0007;
+
+        let pk3 = PK3::from_encrypted_bytes(&encrypt(shiny_pid, id.trainer_id, sample_blocks(), false)).unwrap();
+        assert_eq!(pk3.trainer_id(), id);
+        assert_eq!(pk3.shiny_xor(), 7);
+        assert!(pk3.is_shiny());
+        assert_eq!(pk3.is_shiny(), id.is_shiny(pk3.pid()));
+
+        let pk3 = PK3::from_encrypted_bytes(&encrypt(shiny_pid + 1, id.trainer_id, sample_blocks(), false)).unwrap();
+        assert_eq!(pk3.shiny_xor(), 8);
+        assert!(!pk3.is_shiny());
+    }
+
+    #[test]
+    fn setters() {
+        let bytes = encrypt(7, 0, sample_blocks(), false);
+        let mut pk3 = PK3::from_encrypted_bytes(&bytes).unwrap();
+
+        pk3.set_ivs([1, 2, 3, 4, 5, 6]);
+        assert_eq!(pk3.ivs(), [1, 2, 3, 4, 5, 6]);
+        assert!(pk3.ability_bit(), "Setting the IVs keeps the other bits");
+
+        pk3.set_ivs([31, 32, 0, 0, 0, 63]);
+        assert_eq!(pk3.ivs(), [31, 0, 0, 0, 0, 31]);
+        assert_eq!(PK3::get_sp_defense_iv_from_bytes(pk3.decrypted_bytes()), Ok(31));
+        assert_eq!(pk3.decrypted_bytes()[0x48..0x4C], [0x1F, 0x00, 0x00, 0xBE]);
+        assert!(!pk3.is_egg());
+
+        pk3.set_origin_game(GameVersion::FireRed);
+        pk3.set_ot_gender(Gender::Male);
+        assert_eq!(pk3.origin_game(), Ok(GameVersion::FireRed));
+        assert_eq!(pk3.ot_gender(), Gender::Male);
+        assert_eq!(pk3.met_level(), 5);
+        assert_eq!(pk3.ball(), 4);
+
+        pk3.set_origin_game_raw(9);
+        assert_eq!(pk3.origin_game(), Err(Error::UnknownValue { kind: "GameVersion", value: 9 }));
+        assert_eq!(pk3.origin_game_raw(), 9);
+        assert_eq!(pk3.decrypted_bytes()[0x46..0x48], [0x85, 0x24]);
+
+        pk3.set_species(25);
+        pk3.set_moves([1, 2, 3, 4]);
+        assert_eq!(pk3.species(), 25);
+        assert_eq!(pk3.moves(), [1, 2, 3, 4]);
+        assert_eq!(pk3.decrypted_bytes().len(), 80);
+    }
+
+    #[test]
+    fn encryption_round_trip() {
+        for order in 0..24u32 {
+            let pid = 0x2468_AC00 + order;
+            let mut bytes = encrypt(pid, 0x0BAD_F00D, sample_blocks(), order % 2 == 0);
+            let mut pk3 = PK3::from_encrypted_bytes(&bytes).unwrap();
+
+            // The hand made record has no checksum, which is kept until it's refreshed
+            assert_eq!(pk3.to_encrypted_bytes(), bytes);
+
+            pk3.refresh_checksum();
+            let encrypted = pk3.to_encrypted_bytes();
+            bytes[0x1C..0x1E].copy_from_slice(&pk3.compute_checksum().to_le_bytes());
+            assert_eq!(encrypted, bytes);
+
+            let reloaded = PK3::from_encrypted_bytes(&encrypted).unwrap();
+            assert_eq!(reloaded.validate(), Ok(()));
+            assert_eq!(reloaded.species(), pk3.species());
+        }
+    }
+
+    #[test]
+    fn bad_egg_detection() {
+        let bytes = encrypt(0x0000_0042, 0x1111_2222, sample_blocks(), false);
+        let mut pk3 = PK3::from_encrypted_bytes(&bytes).unwrap();
+
+        let computed = pk3.compute_checksum();
+        assert_eq!(pk3.validate(), Err(BadEggReason::ChecksumMismatch { stored: 0, computed }));
+        assert!(pk3.is_bad_egg());
+
+        pk3.refresh_checksum();
+        assert!(!pk3.is_bad_egg());
+
+        // Editing a valid record leaves the stored checksum behind,
+        // but writing it back computes the checksum again
+        pk3.set_species(1);
+        assert!(pk3.is_bad_egg());
+        let reloaded = PK3::from_encrypted_bytes(&pk3.to_encrypted_bytes()).unwrap();
+        assert_eq!(reloaded.validate(), Ok(()));
+
+        // A record that was a Bad Egg when loaded stays one until it's edited
+        let mut bad_egg = PK3::from_encrypted_bytes(&bytes).unwrap();
+        assert_eq!(bad_egg.to_encrypted_bytes(), bytes);
+        bad_egg.set_species(1);
+        let rewritten = PK3::from_encrypted_bytes(&bad_egg.to_encrypted_bytes()).unwrap();
+        assert_eq!(rewritten.validate(), Ok(()));
+        assert_eq!(rewritten.species(), 1);
+
+        let mut bytes = reloaded.to_encrypted_bytes();
+        bytes[0x13] |= 1;
+        assert_eq!(PK3::from_encrypted_bytes(&bytes).unwrap().validate(), Err(BadEggReason::BadEggFlag));
+
+        // Empty slots are all zero and valid
+        assert_eq!(PK3::from_encrypted_bytes(&[0u8; SIZE_STORED]).unwrap().validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_wrong_sizes() {
+        assert_eq!(PK3::from_encrypted_bytes(&[0u8; 79]), Err(Error::InvalidPokemonSize(79)));
+        assert_eq!(PK3::from_encrypted_bytes(&[0u8; 101]), Err(Error::InvalidPokemonSize(101)));
+    }
+}
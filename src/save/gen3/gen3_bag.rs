@@ -0,0 +1,358 @@
+use pkhex_rs_macros::ByteStruct;
+
+use crate::error::Error;
+use crate::utils::SliceUtils;
+
+use super::gen3_save::{Gen3Game, SaveGen3};
+
+/// Section holding the bag and the PC items
+const BAG_SECTION: usize = 1;
+/// Size of an item slot, the item ID and the quantity
+const SLOT_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PocketKind {
+    Items,
+    KeyItems,
+    Balls,
+    TmHm,
+    Berries,
+    /// The items stored in the PC, not part of the bag
+    PcItems,
+}
+
+impl PocketKind {
+    pub const ALL: [PocketKind; 6] = [
+        PocketKind::Items,
+        PocketKind::KeyItems,
+        PocketKind::Balls,
+        PocketKind::TmHm,
+        PocketKind::Berries,
+        PocketKind::PcItems,
+    ];
+
+    /// Offset of the pocket in the section 1
+    pub fn offset(&self, game: Gen3Game) -> usize {
+        match (game, self) {
+            (Gen3Game::RubySapphire, PocketKind::PcItems) => 0x0498,
+            (Gen3Game::RubySapphire, PocketKind::Items) => 0x0560,
+            (Gen3Game::RubySapphire, PocketKind::KeyItems) => 0x05B0,
+            (Gen3Game::RubySapphire, PocketKind::Balls) => 0x0600,
+            (Gen3Game::RubySapphire, PocketKind::TmHm) => 0x0640,
+            (Gen3Game::RubySapphire, PocketKind::Berries) => 0x0740,
+            (Gen3Game::FireRedLeafGreen, PocketKind::PcItems) => 0x0298,
+            (Gen3Game::FireRedLeafGreen, PocketKind::Items) => 0x0310,
+            (Gen3Game::FireRedLeafGreen, PocketKind::KeyItems) => 0x03B8,
+            (Gen3Game::FireRedLeafGreen, PocketKind::Balls) => 0x0430,
+            (Gen3Game::FireRedLeafGreen, PocketKind::TmHm) => 0x0464,
+            (Gen3Game::FireRedLeafGreen, PocketKind::Berries) => 0x054C,
+            (Gen3Game::Emerald, PocketKind::PcItems) => 0x0498,
+            (Gen3Game::Emerald, PocketKind::Items) => 0x0560,
+            (Gen3Game::Emerald, PocketKind::KeyItems) => 0x05D8,
+            (Gen3Game::Emerald, PocketKind::Balls) => 0x0650,
+            (Gen3Game::Emerald, PocketKind::TmHm) => 0x0690,
+            (Gen3Game::Emerald, PocketKind::Berries) => 0x0790,
+        }
+    }
+
+    /// Amount of item slots in the pocket
+    pub fn capacity(&self, game: Gen3Game) -> usize {
+        match (game, self) {
+            (Gen3Game::RubySapphire, PocketKind::Items | PocketKind::KeyItems) => 20,
+            (Gen3Game::Emerald, PocketKind::Items | PocketKind::KeyItems) => 30,
+            (Gen3Game::RubySapphire | Gen3Game::Emerald, PocketKind::PcItems) => 50,
+            (Gen3Game::RubySapphire | Gen3Game::Emerald, PocketKind::Balls) => 16,
+            (Gen3Game::RubySapphire | Gen3Game::Emerald, PocketKind::TmHm) => 64,
+            (Gen3Game::RubySapphire | Gen3Game::Emerald, PocketKind::Berries) => 46,
+            (Gen3Game::FireRedLeafGreen, PocketKind::PcItems) => 30,
+            (Gen3Game::FireRedLeafGreen, PocketKind::Items) => 42,
+            (Gen3Game::FireRedLeafGreen, PocketKind::KeyItems) => 30,
+            (Gen3Game::FireRedLeafGreen, PocketKind::Balls) => 13,
+            (Gen3Game::FireRedLeafGreen, PocketKind::TmHm) => 58,
+            (Gen3Game::FireRedLeafGreen, PocketKind::Berries) => 43,
+        }
+    }
+
+    /// Most items a single slot can hold, FireRed and LeafGreen
+    /// raised the limit of every pocket to the one of the PC
+    pub fn max_quantity(&self, game: Gen3Game) -> u16 {
+        match (game, self) {
+            (_, PocketKind::KeyItems) => 1,
+            (_, PocketKind::PcItems | PocketKind::Berries) => 999,
+            (Gen3Game::FireRedLeafGreen, _) => 999,
+            _ => 99,
+        }
+    }
+
+    /// Whether the quantities are XORed with the security key,
+    /// the PC items never are
+    pub fn is_encrypted(&self, game: Gen3Game) -> bool {
+        game != Gen3Game::RubySapphire && *self != PocketKind::PcItems
+    }
+}
+
+/// An item and its quantity, which is XORed with the lower half of the security key
+/// in the encrypted pockets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteStruct)]
+#[size(SLOT_SIZE)]
+pub struct ItemSlot {
+    #[offset(0x0)]
+    pub item: u16,
+    #[offset(0x2)]
+    #[xor(key)]
+    pub quantity: u16,
+}
+
+/// The items of a pocket, without the empty slots
+///
+/// Edits are made on the pocket and only reach the save once it's given
+/// back to [`SaveGen3::set_pocket`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pocket {
+    kind: PocketKind,
+    capacity: usize,
+    max_quantity: u16,
+    items: Vec<ItemSlot>,
+}
+
+impl Pocket {
+    /// Reads the item slots of a pocket, `key` is the value XORed with the quantities
+    pub fn from_bytes(data: &[u8], kind: PocketKind, game: Gen3Game, key: u16) -> Result<Self, Error> {
+        let capacity = kind.capacity(game);
+        let mut items = Vec::with_capacity(capacity);
+
+        for slot in data.get_offset(0, capacity * SLOT_SIZE)?.chunks_exact(SLOT_SIZE).map(ItemSlot::view) {
+            let item = slot.item()?;
+            if item != 0 {
+                items.push(ItemSlot { item, quantity: slot.quantity(key)? });
+            }
+        }
+
+        Ok(Pocket { kind, capacity, max_quantity: kind.max_quantity(game), items })
+    }
+
+    /// Writes the items at the start of the pocket and clears the slots left
+    pub fn write_bytes(&self, data: &mut [u8], key: u16) -> Result<(), Error> {
+        let slots = data.get_mutable_offset(0, self.capacity * SLOT_SIZE)?;
+        slots.fill(0);
+
+        for (mut slot, item) in slots.chunks_exact_mut(SLOT_SIZE).map(ItemSlot::view_mut).zip(&self.items) {
+            slot.set_item(item.item)?;
+            slot.set_quantity(key, item.quantity)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn kind(&self) -> PocketKind {
+        self.kind
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn max_quantity(&self) -> u16 {
+        self.max_quantity
+    }
+
+    pub fn items(&self) -> &[ItemSlot] {
+        &self.items
+    }
+
+    /// Amount of an item in the pocket, `0` if it's not there
+    pub fn quantity(&self, item: u16) -> u16 {
+        self.items
+            .iter()
+            .find(|slot| slot.item == item)
+            .map_or(0, |slot| slot.quantity)
+    }
+
+    /// Adds an item to its slot or to a new one, returning the amount held afterwards
+    ///
+    /// The amount is capped at [`Pocket::max_quantity`], and the item `0`
+    /// is the empty slot so it's never added
+    pub fn add_item(&mut self, item: u16, quantity: u16) -> Result<u16, Error> {
+        if item == 0 || quantity == 0 {
+            return Ok(self.quantity(item));
+        }
+
+        let max_quantity = self.max_quantity;
+        if let Some(slot) = self.items.iter_mut().find(|slot| slot.item == item) {
+            slot.quantity = slot.quantity.saturating_add(quantity).min(max_quantity);
+            return Ok(slot.quantity);
+        }
+
+        if self.items.len() >= self.capacity {
+            return Err(Error::PocketFull { capacity: self.capacity });
+        }
+
+        let quantity = quantity.min(max_quantity);
+        self.items.push(ItemSlot { item, quantity });
+        Ok(quantity)
+    }
+
+    /// Takes away some of an item, emptying its slot when none is left,
+    /// returns the amount left
+    pub fn remove_item(&mut self, item: u16, quantity: u16) -> Result<u16, Error> {
+        let index = self
+            .items
+            .iter()
+            .position(|slot| slot.item == item)
+            .ok_or(Error::ItemNotFound(item))?;
+
+        let left = self.items[index].quantity.saturating_sub(quantity);
+        if left == 0 {
+            self.items.remove(index);
+        } else {
+            self.items[index].quantity = left;
+        }
+
+        Ok(left)
+    }
+
+    /// Sorts the items by their ID, like the game does for the TMs and berries
+    pub fn sort(&mut self) {
+        self.items.sort_by_key(|slot| slot.item);
+    }
+
+    pub fn sort_by_key<K: Ord>(&mut self, f: impl FnMut(&ItemSlot) -> K) {
+        self.items.sort_by_key(f);
+    }
+}
+
+impl SaveGen3 {
+    /// Reads a pocket of the bag, or the PC items, decoding their quantities
+    pub fn pocket(&self, kind: PocketKind) -> Result<Pocket, Error> {
+        let size = kind.capacity(self.game_ver) * SLOT_SIZE;
+        let data = self.section(BAG_SECTION)?.get_offset(kind.offset(self.game_ver), size)?;
+        Pocket::from_bytes(data, kind, self.game_ver, self.pocket_key(kind))
+    }
+
+    /// Writes a pocket back into the section it was read from
+    pub fn set_pocket(&mut self, pocket: &Pocket) -> Result<(), Error> {
+        let key = self.pocket_key(pocket.kind);
+        let offset = pocket.kind.offset(self.game_ver);
+        let data = self.section_mut(BAG_SECTION)?.get_mutable_offset(offset, pocket.capacity * SLOT_SIZE)?;
+        pocket.write_bytes(data, key)
+    }
+
+    /// The quantities only use the lower half of the security key
+    fn pocket_key(&self, kind: PocketKind) -> u16 {
+        if kind.is_encrypted(self.game_ver) {
+            self.security_key() as u16
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::save::gen3::gen3_save::{Gen3Game, SaveGen3, SAVE_SIZE};
+    use crate::save::gen3::gen3_utils::write_test_sections;
+
+    use super::{ItemSlot, PocketKind};
+
+    fn test_save(game: Gen3Game, key: u32) -> SaveGen3 {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_sections(&mut image, game, 1, 0, |id, section| match (id, game) {
+            (0, Gen3Game::Emerald) => section[0x00AC..0x00B0].copy_from_slice(&key.to_le_bytes()),
+            (0, Gen3Game::FireRedLeafGreen) => {
+                section[0x00AC] = 1;
+                section[0x0AF8..0x0AFC].copy_from_slice(&key.to_le_bytes());
+            }
+            _ => {}
+        });
+
+        SaveGen3::from_bytes_with_game(&image, game).unwrap()
+    }
+
+    #[test]
+    fn pocket_layouts_fit() {
+        for game in Gen3Game::ALL {
+            let mut ends: Vec<(usize, usize)> = PocketKind::ALL
+                .iter()
+                .map(|kind| (kind.offset(game), kind.offset(game) + kind.capacity(game) * 4))
+                .collect();
+            ends.sort();
+
+            // The pockets follow each other without overlapping
+            assert!(ends.windows(2).all(|pair| pair[0].1 == pair[1].0), "{}", game);
+            assert!(ends.last().unwrap().1 <= game.section_data_size(1));
+        }
+    }
+
+    #[test]
+    fn quantities_use_the_key() {
+        let key = 0xBEEF_1234;
+        let mut save = test_save(Gen3Game::Emerald, key);
+
+        let mut items = save.pocket(PocketKind::Items).unwrap();
+        assert!(items.items().is_empty());
+        assert_eq!(items.add_item(13, 5), Ok(5));
+        save.set_pocket(&items).unwrap();
+
+        let mut pc_items = save.pocket(PocketKind::PcItems).unwrap();
+        pc_items.add_item(13, 5).unwrap();
+        save.set_pocket(&pc_items).unwrap();
+
+        // Only the lower half of the key is used, and the PC items are not encrypted
+        let section = save.section(1).unwrap();
+        assert_eq!(section[0x0560..0x0564], [13, 0, 5 ^ 0x34, 0x12]);
+        assert_eq!(section[0x0498..0x049C], [13, 0, 5, 0]);
+
+        let reloaded = SaveGen3::from_bytes_with_game(&save.to_bytes().unwrap(), Gen3Game::Emerald).unwrap();
+        assert_eq!(reloaded.pocket(PocketKind::Items).unwrap(), items);
+        assert_eq!(reloaded.pocket(PocketKind::PcItems).unwrap().quantity(13), 5);
+
+        // Ruby and Sapphire store the quantities as they are
+        let mut save = test_save(Gen3Game::RubySapphire, 0);
+        let mut balls = save.pocket(PocketKind::Balls).unwrap();
+        balls.add_item(4, 30).unwrap();
+        save.set_pocket(&balls).unwrap();
+        assert_eq!(save.section(1).unwrap()[0x0600..0x0604], [4, 0, 30, 0]);
+    }
+
+    #[test]
+    fn item_slot_view() {
+        let mut bytes = [0u8; 4];
+        let mut slot = ItemSlot::view_mut(&mut bytes);
+        slot.set_item(13).unwrap();
+        slot.set_quantity(0x1234, 5).unwrap();
+
+        assert_eq!(bytes, [13, 0, 0x31, 0x12]);
+        assert_eq!(ItemSlot::view(&bytes).quantity(0x1234), Ok(5));
+        assert_eq!(ItemSlot::view(&bytes).quantity(0), Ok(0x1231));
+        assert!(ItemSlot::view(&bytes[..3]).quantity(0x1234).is_err());
+    }
+
+    #[test]
+    fn add_remove_and_sort() {
+        let save = test_save(Gen3Game::FireRedLeafGreen, 0x0000_FFFF);
+        let mut balls = save.pocket(PocketKind::Balls).unwrap();
+        assert_eq!(balls.capacity(), 13);
+        assert_eq!(balls.max_quantity(), 999);
+
+        for item in (1..=12).rev() {
+            balls.add_item(item, 10).unwrap();
+        }
+        assert_eq!(balls.add_item(4, 995), Ok(999));
+        assert_eq!(balls.add_item(0, 1), Ok(0));
+        balls.add_item(13, 1).unwrap();
+        assert_eq!(balls.add_item(14, 1), Err(Error::PocketFull { capacity: 13 }));
+
+        assert_eq!(balls.remove_item(3, 4), Ok(6));
+        assert_eq!(balls.remove_item(13, 5), Ok(0));
+        assert_eq!(balls.remove_item(13, 1), Err(Error::ItemNotFound(13)));
+        assert_eq!(balls.items().len(), 12);
+
+        balls.sort();
+        assert_eq!(balls.items()[0], ItemSlot { item: 1, quantity: 10 });
+        assert_eq!(balls.items()[3], ItemSlot { item: 4, quantity: 999 });
+
+        let mut key_items = save.pocket(PocketKind::KeyItems).unwrap();
+        assert_eq!(key_items.add_item(259, 3), Ok(1));
+    }
+}
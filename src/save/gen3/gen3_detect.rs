@@ -0,0 +1,283 @@
+use std::ops::RangeInclusive;
+
+use crate::error::Error;
+use crate::pkm::pk3::{self, PK3};
+use crate::utils::SliceUtils;
+
+use super::gen3_bag::{Pocket, PocketKind};
+use super::gen3_save::{Gen3Game, SectionData, Wallet, MAX_COINS, MAX_MONEY, PARTY_SIZE, WALLET_SIZE};
+use super::gen3_utils::get_security_key;
+
+/// Amount of checks done by [`score_game`] on each game
+const CHECK_COUNT: usize = 8;
+
+/// Most Battle Points the Emerald Battle Frontier lets the player hold
+const MAX_BATTLE_POINTS: u16 = 9999;
+
+/// Item IDs from the Master Ball to the Premier Ball
+const BALL_IDS: RangeInclusive<u16> = 1..=12;
+/// Item IDs from TM01 to HM08
+const TM_IDS: RangeInclusive<u16> = 289..=346;
+
+/// How sure the detection is about the game it picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    /// Several games fit the save equally well, the game code at `0xAC` broke the tie
+    Low,
+    /// One game fits better than the others, but some checks disagree with it
+    Medium,
+    /// The section checksums rule out every other game,
+    /// or the game is the only one passing every check
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameDetection {
+    pub game: Gen3Game,
+    pub confidence: Confidence,
+}
+
+/// Guess made by the original detection, only reading the value at `0xAC`
+///
+/// Emerald stores its security key there instead, so an Emerald save
+/// whose key is `0` or `1` is reported as Ruby/Sapphire or FireRed/LeafGreen
+fn game_from_code(trainer_bytes: &[u8]) -> Result<Gen3Game, Error> {
+    Ok(match trainer_bytes.get_u32_le_offset(0x00AC)? {
+        0 => Gen3Game::RubySapphire,
+        1 => Gen3Game::FireRedLeafGreen,
+        _ => Gen3Game::Emerald,
+    })
+}
+
+/// Every slot of the pocket is either empty or has an item that belongs to it
+fn pocket_fits(team_bytes: &[u8], game: Gen3Game, kind: PocketKind, ids: RangeInclusive<u16>) -> Result<bool, Error> {
+    let size = kind.capacity(game) * 4;
+    let pocket = Pocket::from_bytes(team_bytes.get_offset(kind.offset(game), size)?, kind, game, 0)?;
+    Ok(pocket.items().iter().all(|slot| ids.contains(&slot.item)))
+}
+
+/// FireRed and LeafGreen keep a copy of the security key at `0x0F20`,
+/// the other games have no copy and always pass
+fn key_copy_fits(trainer_bytes: &[u8], game: Gen3Game) -> Result<bool, Error> {
+    if game != Gen3Game::FireRedLeafGreen {
+        return Ok(true);
+    }
+
+    Ok(trainer_bytes.get_u32_le_offset(0x0AF8)? == trainer_bytes.get_u32_le_offset(0x0F20)?)
+}
+
+/// Emerald stores the Battle Points of the Battle Frontier at `0x0EB8`,
+/// the other games don't have them and always pass
+fn battle_points_fit(trainer_bytes: &[u8], game: Gen3Game) -> Result<bool, Error> {
+    if game != Gen3Game::Emerald {
+        return Ok(true);
+    }
+
+    Ok(trainer_bytes.get_u16_le_offset(0x0EB8)? <= MAX_BATTLE_POINTS)
+}
+
+/// The party count is at most 6 and none of its members is a Bad Egg
+fn party_fits(team_bytes: &[u8], game: Gen3Game) -> Result<bool, Error> {
+    let offset = game.party_offset();
+    let count = team_bytes.get_u32_le_offset(offset)? as usize;
+    if count > PARTY_SIZE {
+        return Ok(false);
+    }
+
+    for i in 0..count {
+        let bytes = team_bytes.get_offset(offset + 4 + pk3::SIZE_PARTY * i, pk3::SIZE_PARTY)?;
+        if PK3::from_encrypted_bytes(bytes)?.is_bad_egg() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Counts the checks the save passes when read as the given game,
+/// `None` if its sections can't have been written by that game
+fn score_game(game: Gen3Game, trainer_bytes: &[u8], team_bytes: &[u8], large_end_bytes: &[u8]) -> Result<Option<usize>, Error> {
+    // The game only covers its own data size with the checksum, the sections
+    // whose size changes between games are only valid for some of them
+    if !SectionData::is_valid(trainer_bytes, game) || !SectionData::is_valid(large_end_bytes, game) {
+        return Ok(None);
+    }
+
+    let key = get_security_key(trainer_bytes, game)?;
+    let wallet = Wallet::view(team_bytes.get_offset(game.money_offset(), WALLET_SIZE)?);
+    let (money, coins) = (wallet.money(key)?, wallet.coins(key)?);
+
+    let checks: [bool; CHECK_COUNT] = [
+        game_from_code(trainer_bytes)? == game,
+        money <= MAX_MONEY,
+        coins <= MAX_COINS,
+        party_fits(team_bytes, game)?,
+        pocket_fits(team_bytes, game, PocketKind::Balls, BALL_IDS)?,
+        pocket_fits(team_bytes, game, PocketKind::TmHm, TM_IDS)?,
+        key_copy_fits(trainer_bytes, game)?,
+        battle_points_fit(trainer_bytes, game)?,
+    ];
+
+    Ok(Some(checks.iter().filter(|&&passed| passed).count()))
+}
+
+/// Finds the game that wrote a save from its trainer section (0),
+/// the section holding the party and the bag (1) and the last section
+/// of the large block (4)
+///
+/// Every game is checked for section sizes that match its checksums, a money and coin
+/// amount that decode below their limits with its security key, a sane party at its party
+/// offset, and ball and TM pockets holding only balls and TMs. FireRed/LeafGreen also need
+/// both copies of their security key to match, and Emerald Battle Points below their limit.
+/// The game passing the most checks is picked, with the game code at `0xAC` breaking ties
+///
+/// Fails with [`Error::UnknownGame`] if the section checksums don't match any game
+pub fn detect_game(trainer_bytes: &[u8], team_bytes: &[u8], large_end_bytes: &[u8]) -> Result<GameDetection, Error> {
+    let code_game = game_from_code(trainer_bytes)?;
+
+    let mut scores = Vec::new();
+    for game in Gen3Game::ALL {
+        if let Some(score) = score_game(game, trainer_bytes, team_bytes, large_end_bytes)? {
+            scores.push((game, score));
+        }
+    }
+
+    let best = scores.iter().map(|&(_, score)| score).max().ok_or(Error::UnknownGame)?;
+    let tied: Vec<Gen3Game> = scores.iter().filter(|&&(_, score)| score == best).map(|&(game, _)| game).collect();
+
+    let (game, confidence) = match tied[..] {
+        [game] if scores.len() == 1 || best == CHECK_COUNT => (game, Confidence::High),
+        [game] => (game, Confidence::Medium),
+        _ => (*tied.iter().find(|&&game| game == code_game).unwrap_or(&tied[0]), Confidence::Low),
+    };
+
+    Ok(GameDetection { game, confidence })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::save::gen3::gen3_save::{Gen3Game, SaveGen3, SectionData, SAVE_SIZE, SECTION_SIZE};
+    use crate::save::gen3::gen3_utils::write_test_sections;
+
+    use super::{detect_game, Confidence, GameDetection};
+
+    /// Builds a slot whose sections are in order, letting `edit` change
+    /// the sections before their checksums are computed for `game`
+    fn test_image(game: Gen3Game, edit: impl Fn(usize, &mut [u8])) -> Vec<u8> {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_sections(&mut image, game, 1, 0, edit);
+
+        image
+    }
+
+    fn detect(image: &[u8]) -> Result<GameDetection, Error> {
+        detect_game(&image[..SECTION_SIZE], &image[SECTION_SIZE..SECTION_SIZE * 2], &image[SECTION_SIZE * 4..SECTION_SIZE * 5])
+    }
+
+    #[test]
+    fn emerald_with_game_code_key() {
+        // An Emerald security key of 0 reads as the Ruby/Sapphire game code,
+        // but the trainer section has data past the Ruby/Sapphire size
+        // and the money only makes sense as Emerald
+        let image = test_image(Gen3Game::Emerald, |i, section| match i {
+            0 => {
+                section[0x0900] = 0x12;
+                section[0x0AF8..0x0AFC].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+            }
+            1 => section[0x0490..0x0494].copy_from_slice(&3000u32.to_le_bytes()),
+            _ => {}
+        });
+
+        let detection = detect(&image).unwrap();
+        assert_eq!(detection, GameDetection { game: Gen3Game::Emerald, confidence: Confidence::Medium });
+
+        let save = SaveGen3::from_bytes(&image).unwrap();
+        assert_eq!(save.game_ver, Gen3Game::Emerald);
+        assert_eq!(save.trainer_section.as_ref().unwrap().security.security_key, Some(0));
+
+        // The same save with a key of 1 would have been read as FireRed/LeafGreen
+        let key = 1u32;
+        let image = test_image(Gen3Game::Emerald, |i, section| match i {
+            0 => {
+                section[0x00AC..0x00B0].copy_from_slice(&key.to_le_bytes());
+                section[0x0F28] = 0x34;
+            }
+            1 => section[0x0490..0x0494].copy_from_slice(&(3000 ^ key).to_le_bytes()),
+            _ => {}
+        });
+
+        let detection = detect(&image).unwrap();
+        assert_eq!(detection, GameDetection { game: Gen3Game::Emerald, confidence: Confidence::High });
+    }
+
+    #[test]
+    fn game_code_matches() {
+        let image = test_image(Gen3Game::FireRedLeafGreen, |i, section| match i {
+            0 => {
+                section[0x00AC] = 1;
+                section[0x0AF8..0x0AFC].copy_from_slice(&0xA5A5_0F0Fu32.to_le_bytes());
+                section[0x0F20..0x0F24].copy_from_slice(&0xA5A5_0F0Fu32.to_le_bytes());
+            }
+            1 => {
+                section[0x0290..0x0294].copy_from_slice(&(500u32 ^ 0xA5A5_0F0F).to_le_bytes());
+                section[0x0294..0x0296].copy_from_slice(&(20u16 ^ 0x0F0F).to_le_bytes());
+                section[0x0430..0x0432].copy_from_slice(&4u16.to_le_bytes());
+            }
+            _ => {}
+        });
+
+        let detection = detect(&image).unwrap();
+        assert_eq!(detection, GameDetection { game: Gen3Game::FireRedLeafGreen, confidence: Confidence::High });
+
+        // An empty save fits every game, only the game code tells them apart
+        let image = test_image(Gen3Game::RubySapphire, |_, _| {});
+        let detection = detect(&image).unwrap();
+        assert_eq!(detection.game, Gen3Game::RubySapphire);
+    }
+
+    #[test]
+    fn game_specific_checks() {
+        // Emerald reads the FireRed/LeafGreen save below with a key of 1 and
+        // only fails the game code, so the copy of the key is what rules it out
+        let frlg_image = |key_copy: u32| test_image(Gen3Game::FireRedLeafGreen, |i, section| if i == 0 {
+            section[0x00AC] = 1;
+            section[0x0AF8..0x0AFC].copy_from_slice(&0x0F0Fu32.to_le_bytes());
+            section[0x0F20..0x0F24].copy_from_slice(&key_copy.to_le_bytes());
+        });
+
+        let detection = detect(&frlg_image(0x0F0F)).unwrap();
+        assert_eq!(detection, GameDetection { game: Gen3Game::FireRedLeafGreen, confidence: Confidence::High });
+        let detection = detect(&frlg_image(0x0F0E)).unwrap();
+        assert_eq!(detection, GameDetection { game: Gen3Game::FireRedLeafGreen, confidence: Confidence::Low });
+
+        // FireRed/LeafGreen reads the Emerald save below and only fails the game code,
+        // so the Battle Points are what rule it out
+        let emerald_image = |battle_points: u16| test_image(Gen3Game::Emerald, |i, section| if i == 0 {
+            section[0x00AC] = 5;
+            section[0x0EB8..0x0EBA].copy_from_slice(&battle_points.to_le_bytes());
+        });
+
+        let detection = detect(&emerald_image(9999)).unwrap();
+        assert_eq!(detection, GameDetection { game: Gen3Game::Emerald, confidence: Confidence::High });
+        let detection = detect(&emerald_image(10000)).unwrap();
+        assert_eq!(detection, GameDetection { game: Gen3Game::Emerald, confidence: Confidence::Low });
+    }
+
+    #[test]
+    fn forced_and_unknown_games() {
+        // The trainer section only fits Ruby/Sapphire, the large block only fits the others
+        let mut image = test_image(Gen3Game::Emerald, |_, _| {});
+        image[0x0900] = 1;
+        SectionData::fix_checksum(&mut image[..SECTION_SIZE], Gen3Game::RubySapphire).unwrap();
+        image[SECTION_SIZE * 4 + 0x0D00] = 1;
+        SectionData::fix_checksum(&mut image[SECTION_SIZE * 4..SECTION_SIZE * 5], Gen3Game::Emerald).unwrap();
+
+        assert_eq!(detect(&image), Err(Error::UnknownGame));
+        assert_eq!(SaveGen3::from_bytes(&image).unwrap_err(), Error::UnknownGame);
+
+        let save = SaveGen3::from_bytes_with_game(&image, Gen3Game::Emerald).unwrap();
+        assert_eq!(save.game_ver, Gen3Game::Emerald);
+        assert!(save.detection().is_none());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::error::Error;
+
+use super::gen3_save::{Gen3Game, SaveGen3};
+
+/// Number of the first var, the scripts number the vars from it
+pub const EVENT_VAR_BASE: u16 = 0x4000;
+/// Number of vars stored in the save
+pub const EVENT_VAR_COUNT: usize = 0x0100;
+
+impl Gen3Game {
+    /// Offset of the event flags in the large block
+    pub fn event_flags_offset(&self) -> usize {
+        match self {
+            Gen3Game::RubySapphire => 0x1220,
+            Gen3Game::FireRedLeafGreen => 0x0EE0,
+            Gen3Game::Emerald => 0x1270,
+        }
+    }
+
+    /// Number of event flags stored in the save
+    pub fn event_flag_count(&self) -> usize {
+        match self {
+            Gen3Game::RubySapphire | Gen3Game::FireRedLeafGreen => 0x0900,
+            Gen3Game::Emerald => 0x0960,
+        }
+    }
+
+    /// Offset of the vars in the large block, right after the event flags
+    pub fn event_vars_offset(&self) -> usize {
+        self.event_flags_offset() + self.event_flag_count() / 8
+    }
+
+    /// Flags of the gym badges, in the order they are obtained
+    pub fn badge_flags(&self) -> [u16; 8] {
+        let first = match self {
+            Gen3Game::RubySapphire => 0x0807,
+            Gen3Game::FireRedLeafGreen => 0x0820,
+            Gen3Game::Emerald => 0x0867,
+        };
+
+        std::array::from_fn(|badge| first + badge as u16)
+    }
+
+    /// Flag set after entering the Hall of Fame for the first time
+    pub fn game_clear_flag(&self) -> u16 {
+        match self {
+            Gen3Game::RubySapphire => 0x0804,
+            Gen3Game::FireRedLeafGreen => 0x082C,
+            Gen3Game::Emerald => 0x0864,
+        }
+    }
+
+    /// Flag set when the National Pokédex is unlocked
+    pub fn national_dex_flag(&self) -> u16 {
+        match self {
+            Gen3Game::RubySapphire => 0x0836,
+            Gen3Game::FireRedLeafGreen => 0x0840,
+            Gen3Game::Emerald => 0x0896,
+        }
+    }
+
+    /// Var checked by the Pokédex to show the National Pokédex
+    pub fn national_dex_var(&self) -> u16 {
+        match self {
+            Gen3Game::RubySapphire | Gen3Game::Emerald => 0x4046,
+            Gen3Game::FireRedLeafGreen => 0x404E,
+        }
+    }
+}
+
+/// The event flags, set as the story progresses and items are picked up
+///
+/// Edits are made on the flags and only reach the save once they're given
+/// back to [`SaveGen3::set_flags`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventFlags {
+    data: Vec<u8>,
+}
+
+impl EventFlags {
+    /// Number of flags, valid flags go from `0` to `count - 1`
+    pub fn count(&self) -> usize {
+        self.data.len() * 8
+    }
+
+    fn flag_location(&self, flag: u16) -> Result<(usize, u8), Error> {
+        let flag = flag as usize;
+        if flag >= self.count() {
+            return Err(Error::InvalidIndex { index: flag, count: self.count() });
+        }
+
+        Ok((flag / 8, 1 << (flag % 8)))
+    }
+
+    pub fn get(&self, flag: u16) -> Result<bool, Error> {
+        let (byte, mask) = self.flag_location(flag)?;
+        Ok(self.data[byte] & mask != 0)
+    }
+
+    pub fn set(&mut self, flag: u16, value: bool) -> Result<(), Error> {
+        let (byte, mask) = self.flag_location(flag)?;
+        if value {
+            self.data[byte] |= mask;
+        } else {
+            self.data[byte] &= !mask;
+        }
+
+        Ok(())
+    }
+}
+
+/// The vars used by the scripts, numbered from [`EVENT_VAR_BASE`] like in them
+///
+/// Edits are made on the vars and only reach the save once they're given
+/// back to [`SaveGen3::set_vars`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventVars {
+    data: Vec<u16>,
+}
+
+impl EventVars {
+    fn var_index(var: u16) -> Result<usize, Error> {
+        let index = var.wrapping_sub(EVENT_VAR_BASE) as usize;
+        if index >= EVENT_VAR_COUNT {
+            return Err(Error::InvalidIndex { index: var as usize, count: EVENT_VAR_COUNT });
+        }
+
+        Ok(index)
+    }
+
+    pub fn get(&self, var: u16) -> Result<u16, Error> {
+        Ok(self.data[EventVars::var_index(var)?])
+    }
+
+    pub fn set(&mut self, var: u16, value: u16) -> Result<(), Error> {
+        self.data[EventVars::var_index(var)?] = value;
+        Ok(())
+    }
+}
+
+impl SaveGen3 {
+    pub fn flags(&self) -> Result<EventFlags, Error> {
+        let game = self.game_ver;
+        Ok(EventFlags {
+            data: self.large_block_bytes(game.event_flags_offset(), game.event_flag_count() / 8)?,
+        })
+    }
+
+    pub fn set_flags(&mut self, flags: &EventFlags) -> Result<(), Error> {
+        self.set_large_block_bytes(self.game_ver.event_flags_offset(), &flags.data)
+    }
+
+    pub fn vars(&self) -> Result<EventVars, Error> {
+        let bytes = self.large_block_bytes(self.game_ver.event_vars_offset(), EVENT_VAR_COUNT * 2)?;
+        Ok(EventVars {
+            data: bytes.chunks_exact(2).map(|var| u16::from_le_bytes([var[0], var[1]])).collect(),
+        })
+    }
+
+    pub fn set_vars(&mut self, vars: &EventVars) -> Result<(), Error> {
+        let bytes: Vec<u8> = vars.data.iter().flat_map(|var| var.to_le_bytes()).collect();
+        self.set_large_block_bytes(self.game_ver.event_vars_offset(), &bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::save::gen3::gen3_save::{Gen3Game, SaveGen3, LARGE_BLOCK_CHUNK_SIZE, SAVE_SIZE};
+    use crate::save::gen3::gen3_utils::write_test_sections;
+
+    use super::EVENT_VAR_COUNT;
+
+    fn test_save(game: Gen3Game) -> SaveGen3 {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_sections(&mut image, game, 1, 3, |_, _| {});
+
+        SaveGen3::from_bytes_with_game(&image, game).unwrap()
+    }
+
+    #[test]
+    fn layouts() {
+        for game in Gen3Game::ALL {
+            let vars_end = game.event_vars_offset() + EVENT_VAR_COUNT * 2;
+            assert!(vars_end <= LARGE_BLOCK_CHUNK_SIZE * 3 + game.section_data_size(4), "{}", game);
+        }
+
+        assert_eq!(Gen3Game::RubySapphire.event_vars_offset(), 0x1340);
+        assert_eq!(Gen3Game::FireRedLeafGreen.event_vars_offset(), 0x1000);
+        assert_eq!(Gen3Game::Emerald.event_vars_offset(), 0x139C);
+    }
+
+    #[test]
+    fn badges_and_vars() {
+        for game in Gen3Game::ALL {
+            let mut save = test_save(game);
+            let mut flags = save.flags().unwrap();
+            assert_eq!(flags.count(), game.event_flag_count());
+
+            for flag in game.badge_flags() {
+                flags.set(flag, true).unwrap();
+            }
+            flags.set(game.game_clear_flag(), true).unwrap();
+            flags.set(0, true).unwrap();
+            flags.set(0, false).unwrap();
+            assert!(flags.set(game.event_flag_count() as u16, true).is_err());
+            save.set_flags(&flags).unwrap();
+
+            let mut vars = save.vars().unwrap();
+            vars.set(0x4000, 7).unwrap();
+            vars.set(0x40FF, 0xBEEF).unwrap();
+            assert_eq!(vars.set(0x4100, 1), Err(Error::InvalidIndex { index: 0x4100, count: EVENT_VAR_COUNT }));
+            assert!(vars.get(0x3FFF).is_err());
+            save.set_vars(&vars).unwrap();
+
+            let reloaded = SaveGen3::from_bytes_with_game(&save.to_bytes().unwrap(), game).unwrap();
+            let flags = reloaded.flags().unwrap();
+            assert!(game.badge_flags().iter().all(|&flag| flags.get(flag) == Ok(true)), "{}", game);
+            assert_eq!(flags.get(game.game_clear_flag()), Ok(true));
+            assert_eq!(flags.get(0), Ok(false));
+            assert_eq!(flags.get(game.national_dex_flag()), Ok(false));
+
+            let vars = reloaded.vars().unwrap();
+            assert_eq!(vars.get(0x4000), Ok(7));
+            assert_eq!(vars.get(0x40FF), Ok(0xBEEF));
+            assert_eq!(reloaded.large_block(game.event_vars_offset(), 2).unwrap(), [7, 0]);
+        }
+    }
+
+    #[test]
+    fn flags_across_sections() {
+        // The FireRed/LeafGreen flags start at 0x0EE0 and end in the section 2
+        let mut save = test_save(Gen3Game::FireRedLeafGreen);
+        let mut flags = save.flags().unwrap();
+        flags.set(0x0800, true).unwrap();
+        save.set_flags(&flags).unwrap();
+
+        let offset = 0x0EE0 + 0x0800 / 8 - LARGE_BLOCK_CHUNK_SIZE;
+        assert_eq!(save.section(2).unwrap()[offset], 0x01);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::error::{Error, TextError};
+use crate::pkm::pk3::{self, PK3};
+use crate::utils::SliceUtils;
+
+use super::gen3_save::SaveGen3;
+use super::gen3_text::{self, Gen3Charset};
+
+/// Number of boxes in the PC
+pub const BOX_COUNT: usize = 14;
+/// Number of Pokémon in each box
+pub const BOX_SLOTS: usize = 30;
+/// Size of a box name, 8 characters and the terminator
+pub const BOX_NAME_SIZE: usize = 9;
+/// Size of the PC data once the sections 5 to 13 are joined
+pub const PC_STORAGE_SIZE: usize = 0x83D0;
+
+/// First and last sections holding the PC data
+const PC_SECTIONS: std::ops::RangeInclusive<usize> = 5..=13;
+
+const BOXES_OFFSET: usize = 0x0004;
+const BOX_NAMES_OFFSET: usize = BOXES_OFFSET + BOX_COUNT * BOX_SLOTS * pk3::SIZE_STORED;
+const WALLPAPERS_OFFSET: usize = BOX_NAMES_OFFSET + BOX_COUNT * BOX_NAME_SIZE;
+
+/// The PC boxes, joined from the sections 5 to 13 into a single buffer
+///
+/// Edits are made on the buffer and only reach the save once it's given
+/// back to [`SaveGen3::set_pc_storage`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcStorage {
+    data: Vec<u8>,
+    /// Characters of the save, used for the box names
+    charset: Gen3Charset,
+}
+
+impl PcStorage {
+    pub fn from_bytes(data: &[u8], charset: Gen3Charset) -> Result<Self, Error> {
+        Ok(PcStorage {
+            data: data.get_offset(0, PC_STORAGE_SIZE)?.to_vec(),
+            charset,
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn check_box(box_index: usize) -> Result<(), Error> {
+        if box_index >= BOX_COUNT {
+            return Err(Error::InvalidIndex { index: box_index, count: BOX_COUNT });
+        }
+
+        Ok(())
+    }
+
+    fn slot_offset(box_index: usize, slot: usize) -> Result<usize, Error> {
+        PcStorage::check_box(box_index)?;
+        if slot >= BOX_SLOTS {
+            return Err(Error::InvalidIndex { index: slot, count: BOX_SLOTS });
+        }
+
+        Ok(BOXES_OFFSET + (box_index * BOX_SLOTS + slot) * pk3::SIZE_STORED)
+    }
+
+    /// The box shown when opening the PC
+    pub fn current_box(&self) -> u8 {
+        self.data[0]
+    }
+
+    pub fn set_current_box(&mut self, box_index: u8) -> Result<(), Error> {
+        PcStorage::check_box(box_index as usize)?;
+        self.data[0] = box_index;
+        Ok(())
+    }
+
+    /// Decrypts the Pokémon in a slot, empty slots have the species `0`
+    pub fn pokemon(&self, box_index: usize, slot: usize) -> Result<PK3, Error> {
+        let offset = PcStorage::slot_offset(box_index, slot)?;
+        PK3::from_encrypted_bytes(self.data.get_offset(offset, pk3::SIZE_STORED)?)
+    }
+
+    /// Stores a Pokémon in a slot, party records lose their battle stats
+    pub fn set_pokemon(&mut self, box_index: usize, slot: usize, pk3: &PK3) -> Result<(), Error> {
+        let offset = PcStorage::slot_offset(box_index, slot)?;
+        let encrypted = pk3.to_encrypted_bytes();
+        self.data
+            .get_mutable_offset(offset, pk3::SIZE_STORED)?
+            .copy_from_slice(&encrypted[..pk3::SIZE_STORED]);
+        Ok(())
+    }
+
+    /// Empties a slot
+    pub fn clear_pokemon(&mut self, box_index: usize, slot: usize) -> Result<(), Error> {
+        let offset = PcStorage::slot_offset(box_index, slot)?;
+        self.data.get_mutable_offset(offset, pk3::SIZE_STORED)?.fill(0);
+        Ok(())
+    }
+
+    pub fn box_name_bytes(&self, box_index: usize) -> Result<&[u8], Error> {
+        PcStorage::check_box(box_index)?;
+        self.data.get_offset(BOX_NAMES_OFFSET + BOX_NAME_SIZE * box_index, BOX_NAME_SIZE)
+    }
+
+    pub fn box_name(&self, box_index: usize) -> Result<String, Error> {
+        Ok(gen3_text::decode(self.box_name_bytes(box_index)?, self.charset))
+    }
+
+    /// Renames a box, the name can have up to 8 characters
+    pub fn set_box_name(&mut self, box_index: usize, name: &str) -> Result<(), Error> {
+        PcStorage::check_box(box_index)?;
+
+        // The last byte is always kept for the terminator
+        if name.chars().count() >= BOX_NAME_SIZE {
+            return Err(TextError::TooLong { max: BOX_NAME_SIZE - 1 }.into());
+        }
+
+        let buffer = self.data.get_mutable_offset(BOX_NAMES_OFFSET + BOX_NAME_SIZE * box_index, BOX_NAME_SIZE)?;
+        gen3_text::encode_into(name, self.charset, buffer)?;
+        Ok(())
+    }
+
+    pub fn wallpaper(&self, box_index: usize) -> Result<u8, Error> {
+        PcStorage::check_box(box_index)?;
+        Ok(self.data[WALLPAPERS_OFFSET + box_index])
+    }
+
+    pub fn set_wallpaper(&mut self, box_index: usize, wallpaper: u8) -> Result<(), Error> {
+        PcStorage::check_box(box_index)?;
+        self.data[WALLPAPERS_OFFSET + box_index] = wallpaper;
+        Ok(())
+    }
+}
+
+impl SaveGen3 {
+    /// Joins the payload of the sections 5 to 13 into the PC storage
+    pub fn pc_storage(&self) -> Result<PcStorage, Error> {
+        let mut data = Vec::with_capacity(PC_STORAGE_SIZE);
+
+        for section_id in PC_SECTIONS {
+            let size = self.game_ver.section_data_size(section_id);
+            data.extend_from_slice(self.section(section_id)?.get_offset(0, size)?);
+        }
+
+        PcStorage::from_bytes(&data, self.charset())
+    }
+
+    /// Splits the PC storage back into the sections 5 to 13
+    pub fn set_pc_storage(&mut self, storage: &PcStorage) -> Result<(), Error> {
+        let mut offset = 0;
+
+        for section_id in PC_SECTIONS {
+            let size = self.game_ver.section_data_size(section_id);
+            self.section_mut(section_id)?
+                .get_mutable_offset(0, size)?
+                .copy_from_slice(storage.data.get_offset(offset, size)?);
+            offset += size;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::pkm::pk3::{PK3, SIZE_STORED};
+    use crate::save::gen3::gen3_save::{Gen3Game, SaveGen3, SAVE_SIZE};
+    use crate::save::gen3::gen3_text::Gen3Charset;
+    use crate::save::gen3::gen3_utils::write_test_sections;
+
+    use super::{PcStorage, BOX_COUNT, BOX_SLOTS, PC_STORAGE_SIZE};
+
+    fn test_save() -> SaveGen3 {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_sections(&mut image, Gen3Game::RubySapphire, 1, 8, |_, _| {});
+
+        SaveGen3::from_bytes(&image).unwrap()
+    }
+
+    #[test]
+    fn pc_sizes() {
+        let sizes: usize = (5..=13).map(|id| Gen3Game::Emerald.section_data_size(id)).sum();
+        assert_eq!(sizes, PC_STORAGE_SIZE);
+    }
+
+    #[test]
+    fn slots_across_sections() {
+        let mut save = test_save();
+        let mut storage = save.pc_storage().unwrap();
+
+        let mut pk3 = PK3::from_encrypted_bytes(&[0u8; SIZE_STORED]).unwrap();
+        pk3.set_species(151);
+
+        // Box 1 slot 19 starts at 0xF54 and ends past the first section
+        storage.set_pokemon(1, 19, &pk3).unwrap();
+        storage.set_pokemon(BOX_COUNT - 1, BOX_SLOTS - 1, &pk3).unwrap();
+        assert_eq!(
+            storage.set_pokemon(BOX_COUNT, 0, &pk3),
+            Err(Error::InvalidIndex { index: BOX_COUNT, count: BOX_COUNT })
+        );
+        assert_eq!(storage.pokemon(0, BOX_SLOTS), Err(Error::InvalidIndex { index: BOX_SLOTS, count: BOX_SLOTS }));
+
+        storage.set_current_box(3).unwrap();
+        assert!(storage.set_current_box(BOX_COUNT as u8).is_err());
+        storage.set_box_name(2, "LEGENDS").unwrap();
+        assert!(storage.set_box_name(BOX_COUNT, "LEGENDS").is_err());
+        assert!(storage.set_box_name(2, "TOOLONGNAME").is_err());
+        storage.set_wallpaper(13, 11).unwrap();
+
+        save.set_pc_storage(&storage).unwrap();
+        let reloaded = SaveGen3::from_bytes(&save.to_bytes().unwrap()).unwrap();
+        assert!(reloaded.checksums_valid());
+
+        let storage = reloaded.pc_storage().unwrap();
+        assert_eq!(storage.pokemon(1, 19).unwrap().species(), 151);
+        assert!(!storage.pokemon(1, 19).unwrap().is_bad_egg());
+        assert_eq!(storage.pokemon(BOX_COUNT - 1, BOX_SLOTS - 1).unwrap().species(), 151);
+        assert_eq!(storage.pokemon(1, 18).unwrap().species(), 0);
+        assert_eq!(storage.current_box(), 3);
+        assert_eq!(storage.box_name(2).unwrap(), "LEGENDS");
+        assert_eq!(storage.wallpaper(13), Ok(11));
+        assert!(storage.wallpaper(14).is_err());
+
+        // The slot straddles the sections 5 and 6
+        let first_half = &reloaded.section(5).unwrap()[0x0F54..0x0F80];
+        let second_half = &reloaded.section(6).unwrap()[..SIZE_STORED - first_half.len()];
+        let joined = [first_half, second_half].concat();
+        assert_eq!(PK3::from_encrypted_bytes(&joined).unwrap().species(), 151);
+    }
+
+    #[test]
+    fn box_names_use_the_save_charset() {
+        let mut storage = PcStorage::from_bytes(&[0u8; PC_STORAGE_SIZE], Gen3Charset::Japanese).unwrap();
+        storage.set_box_name(0, "ＰＣ").unwrap();
+        assert_eq!(storage.box_name_bytes(0).unwrap()[..3], [0xCA, 0xBD, 0xFF]);
+        assert_eq!(storage.box_name(0).unwrap(), "ＰＣ");
+
+        let international = PcStorage::from_bytes(storage.as_bytes(), Gen3Charset::International).unwrap();
+        assert_eq!(international.box_name(0).unwrap(), "PC");
+    }
+}
@@ -0,0 +1,250 @@
+use crate::error::Error;
+use crate::utils::SliceUtils;
+
+use super::gen3_save::{Gen3Game, SaveGen3};
+
+/// Number of species in the National Pokédex
+pub const SPECIES_COUNT: usize = 386;
+/// Size of the seen and owned bitfields, one bit per species
+pub const DEX_FLAGS_SIZE: usize = 0x34;
+
+const OWNED_OFFSET: usize = 0x0028;
+const SEEN_OFFSET: usize = 0x005C;
+/// The Pokédex mode in Ruby, Sapphire and Emerald, `1` for the National Pokédex
+const DEX_MODE_OFFSET: usize = 0x0019;
+
+impl Gen3Game {
+    /// Sections and offsets of the copies of the seen flags,
+    /// the game compares them to the ones in the trainer section
+    fn seen_mirrors(&self) -> [(usize, usize); 2] {
+        match self {
+            Gen3Game::RubySapphire => [(1, 0x0938), (4, 0x0C0C)],
+            Gen3Game::FireRedLeafGreen => [(1, 0x05F8), (4, 0x0B98)],
+            Gen3Game::Emerald => [(1, 0x0988), (4, 0x0CA4)],
+        }
+    }
+
+    /// Offset of the National Pokédex magic in the trainer section and its value once unlocked
+    fn national_magic(&self) -> (usize, u8) {
+        match self {
+            Gen3Game::RubySapphire | Gen3Game::Emerald => (0x001A, 0xDA),
+            Gen3Game::FireRedLeafGreen => (0x001B, 0xB9),
+        }
+    }
+
+    /// Value of the National Pokédex var once unlocked
+    fn national_var_value(&self) -> u16 {
+        match self {
+            Gen3Game::RubySapphire | Gen3Game::Emerald => 0x0302,
+            Gen3Game::FireRedLeafGreen => 0x6258,
+        }
+    }
+}
+
+/// The seen and owned flags of every species, and whether the National Pokédex is unlocked
+///
+/// Species are numbered as in the National Pokédex, starting from `1`.
+/// Edits are made on the Pokédex and only reach the save once it's given
+/// back to [`SaveGen3::set_pokedex`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pokedex {
+    owned: [u8; DEX_FLAGS_SIZE],
+    seen: [u8; DEX_FLAGS_SIZE],
+    national: bool,
+}
+
+impl Pokedex {
+    fn flag_location(species: u16) -> Result<(usize, u8), Error> {
+        let index = (species as usize).wrapping_sub(1);
+        if index >= SPECIES_COUNT {
+            return Err(Error::InvalidIndex { index: species as usize, count: SPECIES_COUNT });
+        }
+
+        Ok((index / 8, 1 << (index % 8)))
+    }
+
+    fn get_flag(flags: &[u8; DEX_FLAGS_SIZE], species: u16) -> Result<bool, Error> {
+        let (byte, mask) = Pokedex::flag_location(species)?;
+        Ok(flags[byte] & mask != 0)
+    }
+
+    fn set_flag(flags: &mut [u8; DEX_FLAGS_SIZE], species: u16, value: bool) -> Result<(), Error> {
+        let (byte, mask) = Pokedex::flag_location(species)?;
+        if value {
+            flags[byte] |= mask;
+        } else {
+            flags[byte] &= !mask;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_seen(&self, species: u16) -> Result<bool, Error> {
+        Pokedex::get_flag(&self.seen, species)
+    }
+
+    /// Marks a species as seen, unseeing it also removes it from the owned ones
+    pub fn set_seen(&mut self, species: u16, seen: bool) -> Result<(), Error> {
+        Pokedex::set_flag(&mut self.seen, species, seen)?;
+        if !seen {
+            Pokedex::set_flag(&mut self.owned, species, false)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_owned(&self, species: u16) -> Result<bool, Error> {
+        Pokedex::get_flag(&self.owned, species)
+    }
+
+    /// Marks a species as owned, which also marks it as seen like catching it does
+    pub fn set_owned(&mut self, species: u16, owned: bool) -> Result<(), Error> {
+        Pokedex::set_flag(&mut self.owned, species, owned)?;
+        if owned {
+            Pokedex::set_flag(&mut self.seen, species, true)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn seen_count(&self) -> usize {
+        self.seen.iter().map(|byte| byte.count_ones() as usize).sum()
+    }
+
+    pub fn owned_count(&self) -> usize {
+        self.owned.iter().map(|byte| byte.count_ones() as usize).sum()
+    }
+
+    pub fn is_national(&self) -> bool {
+        self.national
+    }
+
+    pub fn set_national(&mut self, national: bool) {
+        self.national = national;
+    }
+}
+
+impl SaveGen3 {
+    /// Reads the Pokédex from the trainer section, the seen flags
+    /// copied in other sections are not read
+    pub fn pokedex(&self) -> Result<Pokedex, Error> {
+        let trainer_bytes = self.section(0)?;
+        let mut owned = [0u8; DEX_FLAGS_SIZE];
+        owned.copy_from_slice(trainer_bytes.get_offset(OWNED_OFFSET, DEX_FLAGS_SIZE)?);
+        let mut seen = [0u8; DEX_FLAGS_SIZE];
+        seen.copy_from_slice(trainer_bytes.get_offset(SEEN_OFFSET, DEX_FLAGS_SIZE)?);
+
+        let (magic_offset, magic) = self.game_ver.national_magic();
+        let national = trainer_bytes.get_offset(magic_offset, 1)?[0] == magic;
+
+        Ok(Pokedex { owned, seen, national })
+    }
+
+    /// Writes the Pokédex into the trainer section and every copy of the seen flags
+    ///
+    /// Unlocking the National Pokédex sets its magic value, event flag and var the way the
+    /// game does, and on Ruby, Sapphire and Emerald also switches the Pokédex to the national mode
+    pub fn set_pokedex(&mut self, pokedex: &Pokedex) -> Result<(), Error> {
+        let game = self.game_ver;
+
+        let trainer_bytes = self.section_mut(0)?;
+        trainer_bytes.get_mutable_offset(OWNED_OFFSET, DEX_FLAGS_SIZE)?.copy_from_slice(&pokedex.owned);
+        trainer_bytes.get_mutable_offset(SEEN_OFFSET, DEX_FLAGS_SIZE)?.copy_from_slice(&pokedex.seen);
+
+        let (magic_offset, magic) = game.national_magic();
+        trainer_bytes.get_mutable_offset(magic_offset, 1)?[0] = if pokedex.national { magic } else { 0 };
+        if game != Gen3Game::FireRedLeafGreen {
+            trainer_bytes.get_mutable_offset(DEX_MODE_OFFSET, 1)?[0] = pokedex.national as u8;
+        }
+
+        for (section_id, offset) in game.seen_mirrors() {
+            self.section_mut(section_id)?
+                .get_mutable_offset(offset, DEX_FLAGS_SIZE)?
+                .copy_from_slice(&pokedex.seen);
+        }
+
+        let mut flags = self.flags()?;
+        flags.set(game.national_dex_flag(), pokedex.national)?;
+        self.set_flags(&flags)?;
+
+        let mut vars = self.vars()?;
+        let value = if pokedex.national { game.national_var_value() } else { 0 };
+        vars.set(game.national_dex_var(), value)?;
+        self.set_vars(&vars)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::save::gen3::gen3_save::{Gen3Game, SaveGen3, SAVE_SIZE};
+    use crate::save::gen3::gen3_utils::write_test_sections;
+
+    use super::{DEX_FLAGS_SIZE, SPECIES_COUNT};
+
+    fn test_save(game: Gen3Game) -> SaveGen3 {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_sections(&mut image, game, 1, 11, |_, _| {});
+
+        SaveGen3::from_bytes_with_game(&image, game).unwrap()
+    }
+
+    #[test]
+    fn seen_and_owned() {
+        let mut save = test_save(Gen3Game::Emerald);
+        let mut pokedex = save.pokedex().unwrap();
+        assert_eq!(pokedex.seen_count(), 0);
+
+        pokedex.set_owned(1, true).unwrap();
+        pokedex.set_seen(386, true).unwrap();
+        pokedex.set_owned(25, true).unwrap();
+        pokedex.set_seen(25, false).unwrap();
+        assert_eq!(pokedex.set_seen(0, true), Err(Error::InvalidIndex { index: 0, count: SPECIES_COUNT }));
+        assert!(pokedex.is_owned(387).is_err());
+
+        assert_eq!(pokedex.is_seen(1), Ok(true));
+        assert_eq!(pokedex.is_owned(386), Ok(false));
+        assert_eq!(pokedex.is_owned(25), Ok(false));
+        assert_eq!((pokedex.seen_count(), pokedex.owned_count()), (2, 1));
+
+        save.set_pokedex(&pokedex).unwrap();
+        let reloaded = SaveGen3::from_bytes_with_game(&save.to_bytes().unwrap(), Gen3Game::Emerald).unwrap();
+        assert_eq!(reloaded.pokedex().unwrap(), pokedex);
+
+        // Every copy of the seen flags matches the one in the trainer section
+        let seen = &reloaded.section(0).unwrap()[0x5C..0x5C + DEX_FLAGS_SIZE];
+        assert_eq!(seen[0], 0x01);
+        assert_eq!(seen[385 / 8], 1 << (385 % 8));
+        assert_eq!(&reloaded.section(1).unwrap()[0x0988..0x0988 + DEX_FLAGS_SIZE], seen);
+        assert_eq!(&reloaded.section(4).unwrap()[0x0CA4..0x0CA4 + DEX_FLAGS_SIZE], seen);
+    }
+
+    #[test]
+    fn national_dex() {
+        for game in Gen3Game::ALL {
+            let mut save = test_save(game);
+            let mut pokedex = save.pokedex().unwrap();
+            assert!(!pokedex.is_national());
+
+            pokedex.set_national(true);
+            save.set_pokedex(&pokedex).unwrap();
+            assert!(save.pokedex().unwrap().is_national(), "{}", game);
+
+            let (flag_offset, mask, var_offset, var) = match game {
+                Gen3Game::RubySapphire => (0x1220 + 0x0836 / 8, 0x40, 0x1340 + 0x8C, [0x02, 0x03]),
+                Gen3Game::FireRedLeafGreen => (0x0EE0 + 0x0840 / 8, 0x01, 0x1000 + 0x9C, [0x58, 0x62]),
+                Gen3Game::Emerald => (0x1270 + 0x0896 / 8, 0x40, 0x139C + 0x8C, [0x02, 0x03]),
+            };
+            assert_eq!(save.large_block(flag_offset, 1).unwrap()[0], mask);
+            assert_eq!(save.large_block(var_offset, 2).unwrap(), var);
+
+            pokedex.set_national(false);
+            save.set_pokedex(&pokedex).unwrap();
+            assert!(!save.pokedex().unwrap().is_national());
+            assert_eq!(save.large_block(flag_offset, 1).unwrap()[0], 0);
+            assert_eq!(save.large_block(var_offset, 2).unwrap(), [0, 0]);
+        }
+    }
+}
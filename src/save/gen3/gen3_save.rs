@@ -1,16 +1,90 @@
-use std::fmt::{Display, Formatter, Result};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::time::Duration;
 
-use crate::byte_struct_test;
+use pkhex_rs_macros::ByteStruct;
 
-#[derive(Debug)]
+use crate::cursor::ByteReader;
+use crate::error::Error;
+use crate::pkm::pk3::PK3;
+use crate::utils::SliceUtils;
+
+use super::gen3_detect::{detect_game, GameDetection};
+use super::gen3_text::Gen3Charset;
+use super::gen3_utils::{get_save_charset, get_section_offsets, get_slot_save_index, parse_party_from_byte_array,
+    parse_trainer_data_from_byte_array, select_active_slot, write_party_to_byte_array, write_trainer_data_to_byte_array};
+
+/// Size of a single save section, footer included
+pub const SECTION_SIZE: usize = 0x1000;
+/// Number of sections that make up a save slot
+pub const SECTION_COUNT: usize = 14;
+/// Size of a save slot, the game alternates between two of them
+pub const SLOT_SIZE: usize = SECTION_SIZE * SECTION_COUNT;
+/// Size of a complete save image, both slots plus the Hall of Fame and extra data
+pub const SAVE_SIZE: usize = 0x20000;
+/// Maximum amount of Pokémon in the party
+pub const PARTY_SIZE: usize = 6;
+/// Magic value stored in the footer of every valid section
+pub const SECTION_SIGNATURE: u32 = 0x08012025;
+/// Amount of data each of the sections 1 to 4 adds to the large block
+pub const LARGE_BLOCK_CHUNK_SIZE: usize = 0x0F80;
+/// Most money the player can carry
+pub const MAX_MONEY: u32 = 999_999;
+/// Most Game Corner coins the player can carry
+pub const MAX_COINS: u16 = 9_999;
+/// Size of the money and the coins that follow it
+pub const WALLET_SIZE: usize = 6;
+/// Hours at which the game stops counting the time played
+pub const MAX_PLAYED_HOURS: u16 = 999;
+/// The time is counted in frames, at the 60 frames per second of the GBA
+pub const FRAMES_PER_SECOND: u8 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Gen3Game {
     RubySapphire,
     FireRedLeafGreen,
     Emerald,
 }
 
+impl Gen3Game {
+    pub const ALL: [Gen3Game; 3] = [Gen3Game::RubySapphire, Gen3Game::FireRedLeafGreen, Gen3Game::Emerald];
+
+    /// Amount of bytes of the section covered by its checksum, the rest of it up
+    /// to the footer is padding
+    ///
+    /// Only the trainer section (0) and the last part of the large block (4)
+    /// differ between games, the remaining sections have the same size everywhere
+    pub fn section_data_size(&self, section_id: usize) -> usize {
+        match (section_id, self) {
+            (0, Gen3Game::RubySapphire) => 0x0890,
+            (0, Gen3Game::FireRedLeafGreen) => 0x0F24,
+            (0, Gen3Game::Emerald) => 0x0F2C,
+            (4, Gen3Game::RubySapphire) => 0x0C40,
+            (4, Gen3Game::FireRedLeafGreen) => 0x0EE8,
+            (4, Gen3Game::Emerald) => 0x0F08,
+            (13, _) => 0x07D0,
+            _ => 0x0F80,
+        }
+    }
+
+    /// Offset of the party count in the section 1, the party follows it
+    pub fn party_offset(&self) -> usize {
+        match self {
+            Gen3Game::RubySapphire | Gen3Game::Emerald => 0x0234,
+            Gen3Game::FireRedLeafGreen => 0x0034,
+        }
+    }
+
+    /// Offset of the money in the section 1, the coins follow it
+    pub fn money_offset(&self) -> usize {
+        match self {
+            Gen3Game::RubySapphire | Gen3Game::Emerald => 0x0490,
+            Gen3Game::FireRedLeafGreen => 0x0290,
+        }
+    }
+}
+
 impl Display for Gen3Game {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Gen3Game::RubySapphire => write!(f, "Ruby/Sapphire"),
             Gen3Game::FireRedLeafGreen => write!(f, "FireRed/LeafGreen"),
@@ -19,31 +93,165 @@ impl Display for Gen3Game {
     }
 }
 
+/// The money and the Game Corner coins, found at [`Gen3Game::money_offset`] in the section 1
+///
+/// Both are XORed with the security key, the coins only with its lower half
+#[derive(Debug, ByteStruct)]
+#[size(WALLET_SIZE)]
+pub struct Wallet {
+    #[offset(0x0)]
+    #[xor(key)]
+    pub money: u32,
+    #[offset(0x4)]
+    #[xor(key: u32)]
+    pub coins: u16,
+}
+
 #[derive(Debug)]
 pub struct KeyCode {
     pub game_code: Option<u32>,
     pub security_key: Option<u32>,
 }
 
-#[derive(Debug)]
+/// The time played, stored as hours, minutes, seconds and frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct PlayedTime {
-    pub time: [u8; 5],
-    pub hours: u16,
-    pub minutes: u8,
-    pub seconds: u8,
-    pub frames: u8,
+    hours: u16,
+    minutes: u8,
+    seconds: u8,
+    frames: u8,
 }
 
-#[derive(Debug)]
+impl PlayedTime {
+    /// The value the game stops at, 999:59:59 and 59 frames
+    pub const MAX: PlayedTime = PlayedTime {
+        hours: MAX_PLAYED_HOURS,
+        minutes: 59,
+        seconds: 59,
+        frames: FRAMES_PER_SECOND - 1,
+    };
+
+    pub fn new(hours: u16, minutes: u8, seconds: u8, frames: u8) -> Result<Self, Error> {
+        let mut time = PlayedTime::default();
+        time.set_hours(hours)?;
+        time.set_minutes(minutes)?;
+        time.set_seconds(seconds)?;
+        time.set_frames(frames)?;
+        Ok(time)
+    }
+
+    /// Reads the time from its 5 bytes in the trainer section,
+    /// the values are kept as they are even if the game would not store them
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut reader = ByteReader::new(data);
+        Ok(PlayedTime {
+            hours: reader.read_u16_le()?,
+            minutes: reader.read_u8()?,
+            seconds: reader.read_u8()?,
+            frames: reader.read_u8()?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 5] {
+        let [low, high] = self.hours.to_le_bytes();
+        [low, high, self.minutes, self.seconds, self.frames]
+    }
+
+    /// Converts a duration into a time, durations past [`PlayedTime::MAX`] are capped to it
+    /// like the game does
+    pub fn from_duration(duration: Duration) -> Self {
+        let seconds = duration.as_secs();
+        if seconds / 3600 > MAX_PLAYED_HOURS as u64 {
+            return PlayedTime::MAX;
+        }
+
+        PlayedTime {
+            hours: (seconds / 3600) as u16,
+            minutes: (seconds / 60 % 60) as u8,
+            seconds: (seconds % 60) as u8,
+            frames: (duration.subsec_nanos() as u64 * FRAMES_PER_SECOND as u64 / 1_000_000_000) as u8,
+        }
+    }
+
+    pub fn to_duration(&self) -> Duration {
+        let seconds = self.hours as u64 * 3600 + self.minutes as u64 * 60 + self.seconds as u64;
+        // Rounded up so converting the duration back gives the same frame
+        let nanos = (self.frames as u64 * 1_000_000_000).div_ceil(FRAMES_PER_SECOND as u64);
+        Duration::from_secs(seconds) + Duration::from_nanos(nanos)
+    }
+
+    /// Adds some time, stopping at [`PlayedTime::MAX`]
+    pub fn saturating_add(&self, duration: Duration) -> Self {
+        PlayedTime::from_duration(self.to_duration().saturating_add(duration))
+    }
+
+    pub fn is_max(&self) -> bool {
+        *self == PlayedTime::MAX
+    }
+
+    pub fn hours(&self) -> u16 {
+        self.hours
+    }
+
+    pub fn minutes(&self) -> u8 {
+        self.minutes
+    }
+
+    pub fn seconds(&self) -> u8 {
+        self.seconds
+    }
+
+    pub fn frames(&self) -> u8 {
+        self.frames
+    }
+
+    /// Sets the hours, up to [`MAX_PLAYED_HOURS`]
+    pub fn set_hours(&mut self, hours: u16) -> Result<(), Error> {
+        self.hours = PlayedTime::check_range(hours, MAX_PLAYED_HOURS)?;
+        Ok(())
+    }
+
+    pub fn set_minutes(&mut self, minutes: u8) -> Result<(), Error> {
+        self.minutes = PlayedTime::check_range(minutes, 59)?;
+        Ok(())
+    }
+
+    pub fn set_seconds(&mut self, seconds: u8) -> Result<(), Error> {
+        self.seconds = PlayedTime::check_range(seconds, 59)?;
+        Ok(())
+    }
+
+    pub fn set_frames(&mut self, frames: u8) -> Result<(), Error> {
+        self.frames = PlayedTime::check_range(frames, FRAMES_PER_SECOND - 1)?;
+        Ok(())
+    }
+
+    fn check_range<T: Into<u32> + Copy>(value: T, max: T) -> Result<T, Error> {
+        if value.into() > max.into() {
+            return Err(Error::ValueOutOfRange { value: value.into(), max: max.into() });
+        }
+
+        Ok(value)
+    }
+}
+
+impl Display for PlayedTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{:02}:{:02}", self.hours, self.minutes, self.seconds)
+    }
+}
+
+/// The 32bit ID of a trainer, the visible TID in the lower half and the SID in the upper half
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TrainerId {
     pub trainer_id: u32,
-    pub secret_id: u16,
-    pub public_id: u16,
 }
 
 #[derive(Debug)]
 pub struct TrainerData {
     pub section_info: Option<SectionData>,
+    /// Characters of the save, used for the name
+    pub charset: Gen3Charset,
     pub name: [u8; 7],
     pub gender: u8,
     pub id: TrainerId,
@@ -51,12 +259,22 @@ pub struct TrainerData {
     pub security: KeyCode,
 }
 
-byte_struct_test! {
-    SectionData => section_id: u16@0x0FF4|le, checksum: u16@0x0FF6|le, signature: u32@0x0FF8|le, save_index: u32@0x0FFC|le
+/// The footer at the end of every section
+#[derive(Debug, ByteStruct)]
+#[size(SECTION_SIZE)]
+pub struct SectionData {
+    #[offset(0x0FF4)]
+    pub section_id: u16,
+    #[offset(0x0FF6)]
+    pub checksum: u16,
+    #[offset(0x0FF8)]
+    pub signature: u32,
+    #[offset(0x0FFC)]
+    pub save_index: u32,
 }
 
 impl Display for SectionData {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "ID: {}, Checksum: {}, Signature: {}, Save Index: {}",
@@ -65,8 +283,307 @@ impl Display for SectionData {
     }
 }
 
-#[derive(Debug)]
 pub struct SaveGen3 {
     pub game_ver: Gen3Game,
     pub trainer_section: Option<TrainerData>,
+    party: Vec<PK3>,
+    detection: Option<GameDetection>,
+    data: Vec<u8>,
+    /// The active slot as it was loaded, kept as the backup when the save is written
+    loaded_slot: Vec<u8>,
+    active_slot: usize,
+    section_offsets: [usize; SECTION_COUNT],
+}
+
+impl SaveGen3 {
+    /// Loads a save from a raw flash dump, reading both slots and picking
+    /// the one with the newest save index among the valid ones
+    ///
+    /// The game is found by [`detect_game`], which fails with [`Error::UnknownGame`]
+    /// if the sections don't match any of them
+    ///
+    /// Fails if the image is smaller than [`SAVE_SIZE`], or with the reason
+    /// slot A is not valid when neither slot holds a complete set of sections
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        SaveGen3::load(bytes, None)
+    }
+
+    /// Loads a save as the given game, skipping the detection
+    ///
+    /// Meant for saves the detection gets wrong, forcing the wrong game
+    /// will read the data from the wrong places and write back checksums
+    /// the game doesn't accept
+    pub fn from_bytes_with_game(bytes: &[u8], game: Gen3Game) -> Result<Self, Error> {
+        SaveGen3::load(bytes, Some(game))
+    }
+
+    fn load(bytes: &[u8], game: Option<Gen3Game>) -> Result<Self, Error> {
+        let image = bytes.get_offset(0, SAVE_SIZE)?;
+
+        let slot_a = get_slot_save_index(image.get_offset(0, SLOT_SIZE)?);
+        let slot_b = get_slot_save_index(image.get_offset(SLOT_SIZE, SLOT_SIZE)?);
+        let active_slot = select_active_slot(slot_a, slot_b)?;
+        let section_offsets = get_section_offsets(image, active_slot)?;
+
+        let trainer_bytes = image.get_offset(section_offsets[0], SECTION_SIZE)?;
+        let team_bytes = image.get_offset(section_offsets[1], SECTION_SIZE)?;
+
+        let (game_ver, detection) = match game {
+            Some(game) => (game, None),
+            None => {
+                let large_end_bytes = image.get_offset(section_offsets[4], SECTION_SIZE)?;
+                let detection = detect_game(trainer_bytes, team_bytes, large_end_bytes)?;
+                (detection.game, Some(detection))
+            }
+        };
+
+        let party = parse_party_from_byte_array(team_bytes, game_ver)?;
+        let charset = get_save_charset(&party, TrainerId::new(TrainerId::get_trainer_id_from_bytes(trainer_bytes)?));
+
+        Ok(SaveGen3 {
+            game_ver,
+            trainer_section: Some(parse_trainer_data_from_byte_array(trainer_bytes, game_ver, charset)?),
+            party,
+            detection,
+            data: image.to_vec(),
+            loaded_slot: image.get_offset(SLOT_SIZE * active_slot, SLOT_SIZE)?.to_vec(),
+            active_slot,
+            section_offsets,
+        })
+    }
+
+    /// How the game was detected, `None` if it was forced with [`SaveGen3::from_bytes_with_game`]
+    pub fn detection(&self) -> Option<&GameDetection> {
+        self.detection.as_ref()
+    }
+
+    /// Index of the slot the save was loaded from, `0` for slot A and `1` for slot B
+    pub fn active_slot(&self) -> usize {
+        self.active_slot
+    }
+
+    /// The key obfuscating the money, coins and bag quantities,
+    /// `0` on Ruby and Sapphire which store them as they are
+    pub fn security_key(&self) -> u32 {
+        self.trainer_section
+            .as_ref()
+            .and_then(|trainer| trainer.security.security_key)
+            .unwrap_or(0)
+    }
+
+    /// Characters used by the texts of the save, see [`get_save_charset`]
+    pub fn charset(&self) -> Gen3Charset {
+        self.trainer_section
+            .as_ref()
+            .map(|trainer| trainer.charset)
+            .unwrap_or(Gen3Charset::International)
+    }
+
+    /// Returns the bytes of the section with the given ID from the active slot,
+    /// regardless of where the rotation placed it
+    pub fn section(&self, section_id: usize) -> Result<&[u8], Error> {
+        let offset = self.section_offset(section_id)?;
+        self.data.get_offset(offset, SECTION_SIZE)
+    }
+
+    pub fn section_mut(&mut self, section_id: usize) -> Result<&mut [u8], Error> {
+        let offset = self.section_offset(section_id)?;
+        self.data.get_mutable_offset(offset, SECTION_SIZE)
+    }
+
+    /// Returns bytes of the large block, the data of the sections 1 to 4 joined together,
+    /// which is what the game offsets for the flags and vars are relative to
+    ///
+    /// Fails with [`Error::OutOfBounds`] if the bytes span two sections
+    pub fn large_block(&self, offset: usize, size: usize) -> Result<&[u8], Error> {
+        let (section_id, offset) = SaveGen3::large_block_location(offset, size)?;
+        self.section(section_id)?.get_offset(offset, size)
+    }
+
+    pub fn large_block_mut(&mut self, offset: usize, size: usize) -> Result<&mut [u8], Error> {
+        let (section_id, offset) = SaveGen3::large_block_location(offset, size)?;
+        self.section_mut(section_id)?.get_mutable_offset(offset, size)
+    }
+
+    /// Copies bytes of the large block, which unlike [`SaveGen3::large_block`]
+    /// can span several sections
+    pub fn large_block_bytes(&self, offset: usize, size: usize) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::with_capacity(size);
+        while bytes.len() < size {
+            let position = offset + bytes.len();
+            let chunk = (LARGE_BLOCK_CHUNK_SIZE - position % LARGE_BLOCK_CHUNK_SIZE).min(size - bytes.len());
+            bytes.extend_from_slice(self.large_block(position, chunk)?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Writes bytes into the large block, splitting them between sections if needed
+    pub fn set_large_block_bytes(&mut self, offset: usize, bytes: &[u8]) -> Result<(), Error> {
+        let mut written = 0;
+        while written < bytes.len() {
+            let position = offset + written;
+            let chunk = (LARGE_BLOCK_CHUNK_SIZE - position % LARGE_BLOCK_CHUNK_SIZE).min(bytes.len() - written);
+            self.large_block_mut(position, chunk)?.copy_from_slice(&bytes[written..written + chunk]);
+            written += chunk;
+        }
+
+        Ok(())
+    }
+
+    fn large_block_location(offset: usize, size: usize) -> Result<(usize, usize), Error> {
+        let len = LARGE_BLOCK_CHUNK_SIZE * 4;
+        if offset.saturating_add(size) > len {
+            return Err(Error::OutOfBounds { offset, size, len });
+        }
+
+        let section_offset = offset % LARGE_BLOCK_CHUNK_SIZE;
+        if section_offset + size > LARGE_BLOCK_CHUNK_SIZE {
+            return Err(Error::OutOfBounds { offset: section_offset, size, len: LARGE_BLOCK_CHUNK_SIZE });
+        }
+
+        Ok((1 + offset / LARGE_BLOCK_CHUNK_SIZE, section_offset))
+    }
+
+    fn section_offset(&self, section_id: usize) -> Result<usize, Error> {
+        self.section_offsets
+            .get(section_id)
+            .copied()
+            .ok_or(Error::InvalidSectionId(section_id as u16))
+    }
+
+    /// Builds a complete save image with the current data, written like the game saves
+    ///
+    /// The sections are copied into the other slot and rotated one section further,
+    /// with the trainer data and the party written back, the next save index,
+    /// the signature and a fresh checksum. The active slot is left as it was loaded,
+    /// so the game keeps the previous save as a backup
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut image = self.data.clone();
+
+        let active_start = SLOT_SIZE * self.active_slot;
+        let next_start = SLOT_SIZE * (1 - self.active_slot);
+        let rotation = (self.section_offset(0)? - active_start) / SECTION_SIZE + 1;
+        let next_offset = |section_id: usize| next_start + SECTION_SIZE * ((section_id + rotation) % SECTION_COUNT);
+
+        for (section_id, offset) in self.section_offsets.into_iter().enumerate() {
+            image.copy_within(offset..offset + SECTION_SIZE, next_offset(section_id));
+        }
+        image.get_mutable_offset(active_start, SLOT_SIZE)?.copy_from_slice(&self.loaded_slot);
+
+        if let Some(trainer) = &self.trainer_section {
+            write_trainer_data_to_byte_array(trainer, image.get_mutable_offset(next_offset(0), SECTION_SIZE)?)?;
+        }
+
+        let team_bytes = image.get_mutable_offset(next_offset(1), SECTION_SIZE)?;
+        write_party_to_byte_array(&self.party, team_bytes, self.game_ver)?;
+
+        let save_index = SectionData::view(self.section(0)?).save_index()?.wrapping_add(1);
+        for section_id in 0..SECTION_COUNT {
+            SectionData::write_footer(image.get_mutable_offset(next_offset(section_id), SECTION_SIZE)?, save_index, self.game_ver)?;
+        }
+
+        Ok(image)
+    }
+
+    pub fn party(&self) -> &[PK3] {
+        &self.party
+    }
+
+    /// Mutable access to the party members, the checksums of edited
+    /// Pokémon are recomputed when the save is written
+    pub fn party_mut(&mut self) -> &mut [PK3] {
+        &mut self.party
+    }
+
+    /// Adds a Pokémon at the end of the party, giving it back if the party
+    /// is full or the record doesn't have the party stats
+    pub fn add_party_member(&mut self, pk3: PK3) -> Result<(), PK3> {
+        if self.party.len() >= PARTY_SIZE || !pk3.is_party() {
+            return Err(pk3);
+        }
+
+        self.party.push(pk3);
+        Ok(())
+    }
+
+    /// Removes a Pokémon from the party, the ones after it move up a slot
+    pub fn remove_party_member(&mut self, index: usize) -> Option<PK3> {
+        (index < self.party.len()).then(|| self.party.remove(index))
+    }
+
+    /// Moves a Pokémon to another slot of the party, shifting the ones in between
+    pub fn move_party_member(&mut self, from: usize, to: usize) -> Result<(), Error> {
+        let count = self.party.len();
+        if let Some(index) = [from, to].into_iter().find(|&index| index >= count) {
+            return Err(Error::InvalidIndex { index, count });
+        }
+
+        let pk3 = self.party.remove(from);
+        self.party.insert(to, pk3);
+        Ok(())
+    }
+
+    /// The money carried by the player
+    pub fn money(&self) -> Result<u32, Error> {
+        self.wallet()?.money(self.security_key())
+    }
+
+    /// Sets the money carried by the player, amounts above [`MAX_MONEY`] are lowered to it
+    pub fn set_money(&mut self, money: u32) -> Result<(), Error> {
+        let key = self.security_key();
+        self.wallet_mut()?.set_money(key, money.min(MAX_MONEY))
+    }
+
+    /// The Game Corner coins
+    pub fn coins(&self) -> Result<u16, Error> {
+        self.wallet()?.coins(self.security_key())
+    }
+
+    /// Sets the Game Corner coins, amounts above [`MAX_COINS`] are lowered to it
+    pub fn set_coins(&mut self, coins: u16) -> Result<(), Error> {
+        let key = self.security_key();
+        self.wallet_mut()?.set_coins(key, coins.min(MAX_COINS))
+    }
+
+    fn wallet(&self) -> Result<WalletView<'_>, Error> {
+        let offset = self.game_ver.money_offset();
+        Ok(Wallet::view(self.section(1)?.get_offset(offset, WALLET_SIZE)?))
+    }
+
+    fn wallet_mut(&mut self) -> Result<WalletViewMut<'_>, Error> {
+        let offset = self.game_ver.money_offset();
+        Ok(Wallet::view_mut(self.section_mut(1)?.get_mutable_offset(offset, WALLET_SIZE)?))
+    }
+
+    /// Checks the signature and checksum of every section in the active slot
+    pub fn checksums_valid(&self) -> bool {
+        self.section_offsets.iter().all(|&offset| {
+            self.data
+                .get_offset(offset, SECTION_SIZE)
+                .is_ok_and(|section| SectionData::is_valid(section, self.game_ver))
+        })
+    }
+
+    /// Recomputes the checksum of every section in the active slot,
+    /// must be called after editing the sections so the game accepts the save
+    pub fn fix_checksums(&mut self) -> Result<(), Error> {
+        for offset in self.section_offsets {
+            SectionData::fix_checksum(self.data.get_mutable_offset(offset, SECTION_SIZE)?, self.game_ver)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for SaveGen3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SaveGen3")
+            .field("game_ver", &self.game_ver)
+            .field("trainer_section", &self.trainer_section)
+            .field("party", &self.party)
+            .field("detection", &self.detection)
+            .field("active_slot", &self.active_slot)
+            .finish_non_exhaustive()
+    }
 }
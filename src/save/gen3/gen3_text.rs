@@ -0,0 +1,184 @@
+use crate::error::TextError;
+
+/// Marks the end of a string, the bytes after it are left as they were (trash bytes)
+pub const TERMINATOR: u8 = 0xFF;
+
+const INTERNATIONAL_ACCENTED: &str = "ÀÁÂÇÈÉÊËÌ\0ÎÏÒÓÔŒÙÚÛÑßàá\0çèéêëì\0îïòóôœùúûñºª";
+const INTERNATIONAL_PUNCTUATION: &str = "!?.-·…“”‘’♂♀$,×/";
+const JAPANESE_KANA: &str = concat!(
+    "あいうえおかきくけこさしすせそたちつてとなにぬねのはひふへほまみむめもやゆよらりるれろわをん",
+    "ぁぃぅぇぉゃゅょがぎぐげござじずぜぞだぢづでどばびぶべぼぱぴぷぺぽっ",
+    "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヲン",
+    "ァィゥェォャュョガギグゲゴザジズゼゾダヂヅデドバビブベボパピプペポッ",
+);
+const JAPANESE_PUNCTUATION: &str = "！？。ー・‥『』「」♂♀円．×／";
+const UMLAUTS: &str = "ÄÖÜäöü";
+
+/// Character tables used by the Gen 3 games, Japanese cartridges
+/// use their own table and every other language shares the international one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gen3Charset {
+    International,
+    Japanese,
+}
+
+fn nth_char(table: &str, index: u8) -> Option<char> {
+    table.chars().nth(index as usize).filter(|&c| c != '\0')
+}
+
+fn offset_char(first: char, byte: u8, first_byte: u8) -> Option<char> {
+    char::from_u32(first as u32 + (byte - first_byte) as u32)
+}
+
+/// Returns the character of a byte, `None` for control codes and unused values
+pub fn decode_char(byte: u8, charset: Gen3Charset) -> Option<char> {
+    match (charset, byte) {
+        (Gen3Charset::International, 0x00) => Some(' '),
+        (Gen3Charset::International, 0x01..=0x2B) => nth_char(INTERNATIONAL_ACCENTED, byte - 0x01),
+        (Gen3Charset::International, 0x2D) => Some('&'),
+        (Gen3Charset::International, 0x2E) => Some('+'),
+        (Gen3Charset::International, 0x35) => Some('='),
+        (Gen3Charset::International, 0x36) => Some(';'),
+        (Gen3Charset::International, 0x51) => Some('¿'),
+        (Gen3Charset::International, 0x52) => Some('¡'),
+        (Gen3Charset::International, 0x5A) => Some('Í'),
+        (Gen3Charset::International, 0x5B) => Some('%'),
+        (Gen3Charset::International, 0x5C) => Some('('),
+        (Gen3Charset::International, 0x5D) => Some(')'),
+        (Gen3Charset::International, 0x68) => Some('â'),
+        (Gen3Charset::International, 0x6F) => Some('í'),
+        (Gen3Charset::International, 0xA1..=0xAA) => offset_char('0', byte, 0xA1),
+        (Gen3Charset::International, 0xAB..=0xBA) => nth_char(INTERNATIONAL_PUNCTUATION, byte - 0xAB),
+        (Gen3Charset::International, 0xBB..=0xD4) => offset_char('A', byte, 0xBB),
+        (Gen3Charset::International, 0xD5..=0xEE) => offset_char('a', byte, 0xD5),
+        (Gen3Charset::International, 0xF0) => Some(':'),
+
+        (Gen3Charset::Japanese, 0x00) => Some('　'),
+        (Gen3Charset::Japanese, 0x01..=0xA0) => nth_char(JAPANESE_KANA, byte - 0x01),
+        (Gen3Charset::Japanese, 0xA1..=0xAA) => offset_char('０', byte, 0xA1),
+        (Gen3Charset::Japanese, 0xAB..=0xBA) => nth_char(JAPANESE_PUNCTUATION, byte - 0xAB),
+        (Gen3Charset::Japanese, 0xBB..=0xD4) => offset_char('Ａ', byte, 0xBB),
+        (Gen3Charset::Japanese, 0xD5..=0xEE) => offset_char('ａ', byte, 0xD5),
+        (Gen3Charset::Japanese, 0xF0) => Some('：'),
+
+        (_, 0xEF) => Some('▶'),
+        (_, 0xF1..=0xF6) => nth_char(UMLAUTS, byte - 0xF1),
+        _ => None,
+    }
+}
+
+/// Returns the byte of a character, `None` if the table doesn't have it
+pub fn encode_char(c: char, charset: Gen3Charset) -> Option<u8> {
+    (0..TERMINATOR).find(|&byte| decode_char(byte, charset) == Some(c))
+}
+
+/// Decodes a string up to the terminator or the end of the data,
+/// values without a character are replaced by `U+FFFD`
+///
+/// # Examples
+/// ```
+/// # use pkhex_rs::save::gen3::gen3_text::{decode, Gen3Charset};
+/// let data = [0xC6, 0xBF, 0xC7, 0xC7, 0xC3, 0xFF, 0x00];
+///
+/// let text = decode(&data, Gen3Charset::International);
+///
+/// # assert_eq!(text, "LEMMI");
+/// ```
+pub fn decode(data: &[u8], charset: Gen3Charset) -> String {
+    data.iter()
+        .take_while(|&&byte| byte != TERMINATOR)
+        .map(|&byte| decode_char(byte, charset).unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Encodes a string followed by the terminator
+pub fn encode(text: &str, charset: Gen3Charset) -> Result<Vec<u8>, TextError> {
+    let mut data = text
+        .chars()
+        .map(|c| encode_char(c, charset).ok_or(TextError::UnencodableCharacter(c)))
+        .collect::<Result<Vec<u8>, TextError>>()?;
+    data.push(TERMINATOR);
+
+    Ok(data)
+}
+
+/// Encodes a string into an existing buffer, the terminator is only written
+/// when the text is shorter than the buffer and the bytes after it are preserved
+///
+/// The buffer is not modified if the text can't be encoded
+///
+/// # Examples
+/// ```
+/// # use pkhex_rs::error::TextError;
+/// # use pkhex_rs::save::gen3::gen3_text::{encode_into, Gen3Charset};
+/// let mut data = [0xC6, 0xBF, 0xC7, 0xC7, 0xC3, 0xFF, 0x00];
+///
+/// encode_into("ASH", Gen3Charset::International, &mut data)?;
+///
+/// # assert_eq!(data, [0xBB, 0xCD, 0xC2, 0xFF, 0xC3, 0xFF, 0x00]);
+/// # Ok::<(), TextError>(())
+/// ```
+pub fn encode_into(text: &str, charset: Gen3Charset, buffer: &mut [u8]) -> Result<(), TextError> {
+    let encoded = encode(text, charset)?;
+    let length = encoded.len() - 1;
+
+    if length > buffer.len() {
+        return Err(TextError::TooLong { max: buffer.len() });
+    }
+
+    let written = encoded.len().min(buffer.len());
+    buffer[..written].copy_from_slice(&encoded[..written]);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, decode_char, encode, encode_char, encode_into, Gen3Charset, TextError, TERMINATOR};
+
+    #[test]
+    fn tables_round_trip() {
+        for charset in [Gen3Charset::International, Gen3Charset::Japanese] {
+            for byte in 0..TERMINATOR {
+                if let Some(c) = decode_char(byte, charset) {
+                    assert_eq!(encode_char(c, charset), Some(byte), "{:?} {:#04X}", charset, byte);
+                }
+            }
+        }
+
+        assert_eq!(decode_char(0x50, Gen3Charset::Japanese), Some('っ'));
+        assert_eq!(decode_char(0xA0, Gen3Charset::Japanese), Some('ッ'));
+        assert_eq!(decode_char(0x0A, Gen3Charset::International), None);
+    }
+
+    #[test]
+    fn decode_and_encode() {
+        let text = "Poké-Fan 9!";
+        let encoded = encode(text, Gen3Charset::International).unwrap();
+
+        assert_eq!(encoded.last(), Some(&TERMINATOR));
+        assert_eq!(decode(&encoded, Gen3Charset::International), text);
+
+        let text = "サトシ";
+        let encoded = encode(text, Gen3Charset::Japanese).unwrap();
+        assert_eq!(encoded, [0x5B, 0x64, 0x5C, TERMINATOR]);
+        assert_eq!(decode(&encoded, Gen3Charset::Japanese), text);
+
+        assert_eq!(decode(&[0x0A, 0xBB], Gen3Charset::International), "\u{FFFD}A");
+    }
+
+    #[test]
+    fn encode_errors() {
+        assert_eq!(encode("Q#", Gen3Charset::International), Err(TextError::UnencodableCharacter('#')));
+        assert_eq!(encode("ア", Gen3Charset::International), Err(TextError::UnencodableCharacter('ア')));
+
+        let mut buffer = [0xAB; 5];
+        assert_eq!(encode_into("TOOLONG", Gen3Charset::International, &mut buffer), Err(TextError::TooLong { max: 5 }));
+        assert_eq!(encode_into("A#", Gen3Charset::International, &mut buffer), Err(TextError::UnencodableCharacter('#')));
+        assert_eq!(buffer, [0xAB; 5]);
+
+        // A text filling the whole buffer has no room for the terminator
+        encode_into("ABCDE", Gen3Charset::International, &mut buffer).unwrap();
+        assert_eq!(buffer, [0xBB, 0xBC, 0xBD, 0xBE, 0xBF]);
+    }
+}
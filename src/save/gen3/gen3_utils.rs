@@ -1,72 +1,261 @@
-use core::array::TryFromSliceError;
 use pkhex_rs_macros::{data_get_set_proc, byte_parser_proc};
 
+use crate::cursor::{ByteReader, ByteWriter};
+use crate::error::Error;
+use crate::pkm::pk3::{self, PK3};
 use crate::utils::SliceUtils;
 
-use super::gen3_save::{Gen3Game, KeyCode, SaveGen3, SectionData, TrainerData, TrainerId, PlayedTime};
+use super::gen3_text::{self, Gen3Charset};
+use super::gen3_save::{Gen3Game, KeyCode, SectionData, TrainerData, TrainerId, PlayedTime,
+    PARTY_SIZE, SECTION_COUNT, SECTION_SIGNATURE, SECTION_SIZE, SLOT_SIZE};
+
+/// Computes the checksum of a section, the sum of its data as 32bit words
+/// with both halves of the result folded into 16 bits
+///
+/// # Examples
+/// ```
+/// # use pkhex_rs::save::gen3::gen3_utils::get_section_checksum;
+/// let data = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00];
+///
+/// let checksum = get_section_checksum(&data, 8)?;
+///
+/// # assert_eq!(checksum, 0x0A);
+/// # Ok::<(), pkhex_rs::error::Error>(())
+/// ```
+pub fn get_section_checksum(data: &[u8], size: usize) -> Result<u16, Error> {
+    let sum = data
+        .get_offset(0, size)?
+        .chunks_exact(4)
+        .fold(0u32, |sum, word| sum.wrapping_add(u32::from_le_bytes([word[0], word[1], word[2], word[3]])));
+
+    Ok(((sum >> 16) as u16).wrapping_add(sum as u16))
+}
 
-pub fn get_security_key_or_game_code(block_a: u32, block_b: u32, save: &mut SaveGen3) -> KeyCode {
-    if block_a == 0x00000000 {
-        save.game_ver = Gen3Game::RubySapphire;
-        KeyCode {
-            game_code: Some(0x00000000),
-            security_key: None
+impl SectionData {
+    /// Copies the footer out of the section
+    pub fn new(data: &[u8]) -> Result<Self, Error> {
+        let view = SectionData::view(data);
+        Ok(SectionData {
+            section_id: view.section_id()?,
+            checksum: view.checksum()?,
+            signature: view.signature()?,
+            save_index: view.save_index()?,
+        })
+    }
+
+    /// Computes the checksum of the section using the data size of the given game
+    pub fn compute_checksum(data: &[u8], game: Gen3Game) -> Result<u16, Error> {
+        let section_id = SectionData::view(data).section_id()?;
+        if section_id as usize >= SECTION_COUNT {
+            return Err(Error::InvalidSectionId(section_id));
         }
-    } else if block_a == 0x00000001 {
-        save.game_ver = Gen3Game::FireRedLeafGreen;
-        KeyCode {
-            game_code: Some(0x00000001),
-            security_key: Some(block_b)
+
+        get_section_checksum(data, game.section_data_size(section_id as usize))
+    }
+
+    /// Checks that the section has the signature, a known ID,
+    /// and that the stored checksum matches its data
+    pub fn validate(data: &[u8], game: Gen3Game) -> Result<(), Error> {
+        let info = SectionData::new(data)?;
+
+        if info.signature != SECTION_SIGNATURE {
+            return Err(Error::BadSignature { section_id: info.section_id, signature: info.signature });
         }
-    } else {
-        save.game_ver = Gen3Game::Emerald;
-        KeyCode {
-            game_code: None,
-            security_key: Some(block_a)
+
+        let computed = SectionData::compute_checksum(data, game)?;
+        if info.checksum != computed {
+            return Err(Error::BadChecksum { section_id: info.section_id, stored: info.checksum, computed });
         }
+
+        Ok(())
+    }
+
+    pub fn is_valid(data: &[u8], game: Gen3Game) -> bool {
+        SectionData::validate(data, game).is_ok()
+    }
+
+    pub fn fix_checksum(data: &mut [u8], game: Gen3Game) -> Result<(), Error> {
+        let checksum = SectionData::compute_checksum(data, game)?;
+        SectionData::view_mut(data).set_checksum(checksum)
+    }
+
+    /// Stamps the signature and save index into the footer, then updates the checksum
+    pub fn write_footer(data: &mut [u8], save_index: u32, game: Gen3Game) -> Result<(), Error> {
+        let mut footer = SectionData::view_mut(data);
+        footer.set_signature(SECTION_SIGNATURE)?;
+        footer.set_save_index(save_index)?;
+        SectionData::fix_checksum(data, game)
     }
 }
 
-pub fn parse_trainer_data_from_byte_array(section_bytes: &[u8], save: &mut SaveGen3) -> Result<TrainerData, TryFromSliceError> {
-    Ok(TrainerData {
-        section_info: Some(SectionData::new(section_bytes)),
-        name: <[u8; 7]>::try_from(section_bytes.get_offset(0x0, 7))?,
-        gender: section_bytes[8],
-        id: TrainerId {
-            trainer_id: section_bytes.get_u32_le_offset(0x000A)?,
-            secret_id: section_bytes.get_u16_le_offset(0x000A)?,
-            public_id: section_bytes.get_u16_le_offset(0x000A+2)?,
+/// Returns the save index of a slot, or why the slot is not a complete save
+/// (a section fails its signature or checksum, an ID is repeated or out of range,
+/// or the sections don't all share the same save index)
+///
+/// The game is not known at this point, so a checksum is accepted if it matches
+/// the data size of any of them, otherwise the error reports the Emerald one
+pub fn get_slot_save_index(slot_bytes: &[u8]) -> Result<u32, Error> {
+    let mut found_ids = [false; SECTION_COUNT];
+    let save_index = SectionData::view(slot_bytes).save_index()?;
+
+    for i in 0..SECTION_COUNT {
+        let section = slot_bytes.get_offset(SECTION_SIZE * i, SECTION_SIZE)?;
+
+        if !Gen3Game::ALL.iter().any(|&game| SectionData::is_valid(section, game)) {
+            SectionData::validate(section, Gen3Game::Emerald)?;
+        }
+
+        let footer = SectionData::view(section);
+        let section_id = footer.section_id()?;
+        let found_index = footer.save_index()?;
+        if found_index != save_index {
+            return Err(Error::MismatchedSaveIndex { section_id, expected: save_index, found: found_index });
+        }
+
+        let found = found_ids
+            .get_mut(section_id as usize)
+            .ok_or(Error::InvalidSectionId(section_id))?;
+        if *found {
+            return Err(Error::InvalidSectionId(section_id));
+        }
+        *found = true;
+    }
+
+    Ok(save_index)
+}
+
+/// Picks the slot to load from the save indexes of slot A and B,
+/// failing with the error of slot A when neither is valid
+///
+/// The comparison wraps around, so a slot whose counter overflowed
+/// back to `0` is still considered newer than one at `u32::MAX`
+pub fn select_active_slot(slot_a: Result<u32, Error>, slot_b: Result<u32, Error>) -> Result<usize, Error> {
+    match (slot_a, slot_b) {
+        (Ok(a), Ok(b)) => Ok(if (b.wrapping_sub(a) as i32) > 0 { 1 } else { 0 }),
+        (Ok(_), Err(_)) => Ok(0),
+        (Err(_), Ok(_)) => Ok(1),
+        (Err(error), Err(_)) => Err(error),
+    }
+}
+
+/// Maps every section ID to its absolute offset in the save image for the given slot
+pub fn get_section_offsets(image: &[u8], slot: usize) -> Result<[usize; SECTION_COUNT], Error> {
+    let mut offsets = [0; SECTION_COUNT];
+
+    for i in 0..SECTION_COUNT {
+        let offset = SLOT_SIZE * slot + SECTION_SIZE * i;
+        let section_id = SectionData::view(image.get_offset(offset, SECTION_SIZE)?).section_id()?;
+        *offsets
+            .get_mut(section_id as usize)
+            .ok_or(Error::InvalidSectionId(section_id))? = offset;
+    }
+
+    Ok(offsets)
+}
+
+/// Splits the values at `0xAC` and `0x0AF8` of the trainer section by what they mean for the game,
+/// Ruby and Sapphire store a game code, FireRed and LeafGreen a game code and the security key,
+/// and Emerald only the security key
+pub fn get_security_key_or_game_code(block_a: u32, block_b: u32, game: Gen3Game) -> KeyCode {
+    match game {
+        Gen3Game::RubySapphire => KeyCode {
+            game_code: Some(block_a),
+            security_key: None
         },
-        time: PlayedTime {
-            time: <[u8; 5]>::try_from(section_bytes.get_offset(0x000E, 5))?,
-            hours: section_bytes.get_u16_le_offset(0x000E)?,
-            minutes: section_bytes[0x000E+2],
-            seconds: section_bytes[0x000E+3],
-            frames: section_bytes[0x000E+4],
+        Gen3Game::FireRedLeafGreen => KeyCode {
+            game_code: Some(block_a),
+            security_key: Some(block_b)
         },
+        Gen3Game::Emerald => KeyCode {
+            game_code: None,
+            security_key: Some(block_a)
+        },
+    }
+}
+
+/// Returns the key used to obfuscate the money, coins and item quantities,
+/// Ruby and Sapphire don't use one so it's always `0` for them
+pub fn get_security_key(section_bytes: &[u8], game: Gen3Game) -> Result<u32, Error> {
+    match game {
+        Gen3Game::RubySapphire => Ok(0),
+        Gen3Game::FireRedLeafGreen => section_bytes.get_u32_le_offset(0x0AF8),
+        Gen3Game::Emerald => section_bytes.get_u32_le_offset(0x00AC),
+    }
+}
+
+/// Gen 3 saves don't store their language, so their texts use the characters of the first
+/// party Pokémon caught by the player, or the international ones if there's none
+///
+/// Eggs are skipped, the games give them the Japanese language until they hatch
+pub fn get_save_charset(party: &[PK3], trainer_id: TrainerId) -> Gen3Charset {
+    party.iter()
+        .find(|pk3| !pk3.is_egg() && pk3.ot_id() == trainer_id.trainer_id)
+        .map(PK3::charset)
+        .unwrap_or(Gen3Charset::International)
+}
+
+pub fn parse_trainer_data_from_byte_array(section_bytes: &[u8], game: Gen3Game, charset: Gen3Charset) -> Result<TrainerData, Error> {
+    // The name is followed by a padding byte and the gender by an unused one
+    let mut reader = ByteReader::new(section_bytes);
+    let name = reader.read_array()?;
+    reader.skip(1)?;
+    let gender = reader.read_u8()?;
+    reader.skip(1)?;
+    let id = TrainerId::new(reader.read_u32_le()?);
+    let time = PlayedTime::from_bytes(reader.read_bytes(5)?)?;
+
+    Ok(TrainerData {
+        section_info: Some(SectionData::new(section_bytes)?),
+        charset,
+        name,
+        gender,
+        id,
+        time,
         security: get_security_key_or_game_code(
             section_bytes.get_u32_le_offset(0x00AC)?,
-            section_bytes.get_u32_le_offset(0x0AF8)?, save),
+            section_bytes.get_u32_le_offset(0x0AF8)?, game),
     })
 }
 
-macro_rules! byte_parser {
-    ($($field_name:ident : $type:ty => $offset:expr ; $endianess:ident),+) => {
-        $(
-            paste::paste! {
-                pub fn [<get_ $field_name>](data: &[u8]) -> $type {
-                    SliceUtils::[<get_ $type _ $endianess _offset>](data, $offset).unwrap()
-                }
+/// Writes the editable trainer fields back into the trainer section,
+/// the security data is derived from the save and left untouched
+pub fn write_trainer_data_to_byte_array(trainer: &TrainerData, section_bytes: &mut [u8]) -> Result<(), Error> {
+    let mut writer = ByteWriter::new(section_bytes);
+    writer.write_array(trainer.name)?;
+    writer.skip(1)?;
+    writer.write_u8(trainer.gender)?;
+    writer.skip(1)?;
+    writer.write_u32_le(trainer.id.trainer_id)?;
+    writer.write_array(trainer.time.to_bytes())
+}
 
-                pub fn [<set_ $field_name>](data: &mut [u8], value: $type) {
-                    let value_bytes = $type::[<to_ $endianess _bytes>](value);
-                    data.write_into(&value_bytes, $offset);
-                }
-            }
-        )+
-    };
+/// Reads the party from the section 1, a count above the party size is
+/// treated as a full party
+pub fn parse_party_from_byte_array(section_bytes: &[u8], game: Gen3Game) -> Result<Vec<PK3>, Error> {
+    let mut reader = ByteReader::new(section_bytes);
+    reader.seek(game.party_offset())?;
+    let count = (reader.read_u32_le()? as usize).min(PARTY_SIZE);
+
+    (0..count)
+        .map(|_| PK3::from_encrypted_bytes(reader.read_bytes(pk3::SIZE_PARTY)?))
+        .collect()
 }
 
+/// Writes the party into the section 1, clearing the unused slots
+pub fn write_party_to_byte_array(party: &[PK3], section_bytes: &mut [u8], game: Gen3Game) -> Result<(), Error> {
+    let offset = game.party_offset();
+    section_bytes.get_mutable_offset(offset, 4)?.copy_from_slice(&(party.len() as u32).to_le_bytes());
+
+    for i in 0..PARTY_SIZE {
+        let slot = section_bytes.get_mutable_offset(offset + 4 + pk3::SIZE_PARTY * i, pk3::SIZE_PARTY)?;
+        match party.get(i) {
+            Some(pk3) => slot.copy_from_slice(&pk3.to_encrypted_bytes()[..pk3::SIZE_PARTY]),
+            None => slot.fill(0),
+        }
+    }
+
+    Ok(())
+}
 
 #[macro_export]
 macro_rules! byte_struct_test {
@@ -82,17 +271,17 @@ macro_rules! byte_struct_test {
 
         paste::paste! {
             impl $name {
-                pub fn new(data: &[u8]) -> Self {
-                    $name {
+                pub fn new(data: &[u8]) -> Result<Self, $crate::error::Error> {
+                    Ok($name {
                         $(
-                            $field_name: $name::[<get_ $field_name>](&data),
+                            $field_name: $name::[<get_ $field_name>](&data)?,
                         )+
-                    }
+                    })
                 }
 
                 $(
-                    pub fn [<get_ $field_name>](data: &[u8]) -> $type {
-                        SliceUtils::[<get_ $type _ $endianess _offset>](data, $offset).unwrap()
+                    pub fn [<get_ $field_name>](data: &[u8]) -> Result<$type, $crate::error::Error> {
+                        SliceUtils::[<get_ $type _ $endianess _offset>](data, $offset)
                     }
                 )+
             }
@@ -100,67 +289,461 @@ macro_rules! byte_struct_test {
     };
 }
 
-#[allow(unused_macros)]
-macro_rules! bytes_get_set {
-    ($var_name:ident : $type:ty; $data:ident $(;get => $getter:block)? $(;set, $value:ident => $setter:block)?) => {
-        paste::paste! {
-            $(
-                pub fn [<get_ $var_name _from_bytes>]($data: &[u8]) -> $type $getter
-            )?
+impl TrainerData {
+    /// Decodes the trainer name using the characters of the save
+    pub fn name(&self) -> String {
+        gen3_text::decode(&self.name, self.charset)
+    }
 
-            $(
-                pub fn [<set_ $var_name> _to_bytes]($data: &mut [u8], $value: $type) $setter
-            )?
-        }
+    /// Encodes the trainer name using the characters of the save,
+    /// keeping the trash bytes after the terminator
+    pub fn set_name(&mut self, name: &str) -> Result<(), Error> {
+        Ok(gen3_text::encode_into(name, self.charset, &mut self.name)?)
     }
 }
 
-macro_rules! self_get_set {
-    ($var_name:ident : $type:ty; $self:ident $(;set, $value:ident => $setter:expr)?) => {
-        paste::paste! {
-            pub fn [<get_ $var_name>](&$self) -> &$type {
-                &$self.$var_name
-            }
+impl TrainerId {
+    byte_parser_proc! { #![size(SECTION_SIZE)]
+        #[union] trainer_id: u32@0x000A,
+        tid: u16@0x000A#le,
+        sid: u16@0x000A+2
+    }
 
-            $(
-                pub fn [<set_ $var_name>](&mut $self,  $value: $type) {
-                    $self.$var_name = $value
-                }
-            )?
-        }
+    pub fn new(trainer_id: u32) -> Self {
+        TrainerId { trainer_id }
     }
-}
 
-impl TrainerId {
-    byte_parser_proc! { trainer_id : u32@0x000A }
-    byte_parser_proc! { secret_id: u16@0x000A#le, public_id: u16@0x000A+2 }
-}
+    pub fn from_parts(tid: u16, sid: u16) -> Self {
+        TrainerId::new((sid as u32) << 16 | tid as u32)
+    }
 
-impl PlayedTime {
-    byte_parser! { 
-        hours: u32 => 0x000E;le,
-        minutes: u32 => 0x000E+2;le,
-        seconds: u32 => 0x000E+3;le,
-        frames: u32 => 0x000E+4;le
+    /// The ID shown on the trainer card
+    pub fn tid(&self) -> u16 {
+        self.trainer_id as u16
+    }
+
+    /// The secret ID, never shown in game
+    pub fn sid(&self) -> u16 {
+        (self.trainer_id >> 16) as u16
+    }
+
+    pub fn set_tid(&mut self, tid: u16) {
+        *self = TrainerId::from_parts(tid, self.sid());
+    }
+
+    pub fn set_sid(&mut self, sid: u16) {
+        *self = TrainerId::from_parts(self.tid(), sid);
+    }
+
+    /// XOR of both IDs and both halves of a PID, a Pokémon is shiny when it's below 8
+    ///
+    /// # Examples
+    /// ```
+    /// # use pkhex_rs::save::gen3::gen3_save::TrainerId;
+    /// let id = TrainerId::from_parts(24294, 38834);
+    ///
+    /// let xor = id.shiny_xor(0x9D8E_2A1C);
+    ///
+    /// # assert_eq!(xor, 24294 ^ 38834 ^ 0x9D8E ^ 0x2A1C);
+    /// ```
+    pub fn shiny_xor(&self, pid: u32) -> u16 {
+        self.tid() ^ self.sid() ^ (pid >> 16) as u16 ^ pid as u16
     }
 
-    // Example without proc-macros
-    // bytes_get_set! { time: [u8; 5]; data;
-    //     get => { <[u8; 5]>::try_from(data.get_offset(0x000E, 5)).unwrap() }
-    // }
+    pub fn is_shiny(&self, pid: u32) -> bool {
+        self.shiny_xor(pid) < 8
+    }
+}
 
+impl PlayedTime {
     data_get_set_proc! { time: [u8; 5];
-        get => { <[u8; 5]>::try_from(data.get_offset(0x000E, 5)).unwrap() }
+        get => { let time = data.get_offset(0x000E, 5)?; Ok([time[0], time[1], time[2], time[3], time[4]]) }
     }
+}
 
-    self_get_set! { time: [u8; 5]; self;
-        set, value => value
+/// Fills a slot for the tests with the sections rotated by `rotation` and tagged with
+/// `save_index`, letting `edit` change each section, given its ID, before its footer is written
+#[cfg(test)]
+pub(crate) fn write_test_sections(slot: &mut [u8], game: Gen3Game, save_index: u32, rotation: usize, mut edit: impl FnMut(usize, &mut [u8])) {
+    for id in 0..SECTION_COUNT {
+        let offset = SECTION_SIZE * ((id + rotation) % SECTION_COUNT);
+        let section = &mut slot[offset..offset + SECTION_SIZE];
+        section[0x0FF4..0x0FF6].copy_from_slice(&(id as u16).to_le_bytes());
+        edit(id, section);
+        SectionData::write_footer(section, save_index, game).unwrap();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::save::gen3::gen3_save::{TrainerId, PlayedTime};
+    use crate::save::gen3::gen3_save::{TrainerId, PlayedTime, SaveGen3, Gen3Game, SectionData,
+        MAX_COINS, MAX_MONEY, SECTION_COUNT, SECTION_SIZE, SLOT_SIZE, SAVE_SIZE};
+
+    use crate::error::Error;
+    use std::time::Duration;
+
+    use crate::pkm::pk3::{PK3, SIZE_PARTY};
+    use crate::save::gen3::gen3_text::Gen3Charset;
+    use crate::utils::SliceUtils;
+
+    use super::{get_section_checksum, get_slot_save_index, select_active_slot, write_party_to_byte_array,
+        write_test_sections};
+
+    /// Writes a slot whose sections are rotated by `rotation` and tagged with `save_index`
+    fn write_test_slot(image: &mut [u8], slot: usize, save_index: u32, rotation: usize) {
+        write_test_sections(&mut image[SLOT_SIZE * slot..SLOT_SIZE * (slot + 1)], Gen3Game::Emerald, save_index, rotation, |_, _| {});
+    }
+
+    #[test]
+    fn picks_newest_valid_slot() {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_slot(&mut image, 0, 7, 3);
+        write_test_slot(&mut image, 1, 8, 4);
+        let rotated_trainer = &mut image[SLOT_SIZE + SECTION_SIZE * 4..SLOT_SIZE + SECTION_SIZE * 5];
+        rotated_trainer[0] = 0xBB;
+        SectionData::fix_checksum(rotated_trainer, Gen3Game::Emerald).unwrap();
+
+        let save = SaveGen3::from_bytes(&image).unwrap();
+        assert_eq!(save.active_slot(), 1);
+        assert_eq!(save.section(0).unwrap()[0], 0xBB);
+        assert_eq!(save.section(SECTION_COUNT), Err(Error::InvalidSectionId(SECTION_COUNT as u16)));
+
+        // Breaking a signature on slot B makes slot A the only valid one
+        image[SLOT_SIZE + 0x0FF8] = 0;
+        let save = SaveGen3::from_bytes(&image).unwrap();
+        assert_eq!(save.active_slot(), 0);
+    }
+
+    #[test]
+    fn slot_validation() {
+        let mut image = vec![0u8; SAVE_SIZE];
+        let bad_signature = Error::BadSignature { section_id: 0, signature: 0 };
+        assert_eq!(get_slot_save_index(&image[..SLOT_SIZE]), Err(bad_signature.clone()));
+        assert_eq!(SaveGen3::from_bytes(&image).unwrap_err(), bad_signature);
+        assert_eq!(
+            SaveGen3::from_bytes(&image[..SLOT_SIZE]).unwrap_err(),
+            Error::OutOfBounds { offset: 0, size: SAVE_SIZE, len: SLOT_SIZE }
+        );
+
+        write_test_slot(&mut image, 0, 1, 0);
+        assert_eq!(get_slot_save_index(&image[..SLOT_SIZE]), Ok(1));
+
+        // A slot left half written by an interrupted save mixes two save indexes
+        let torn = &mut image[SECTION_SIZE * 5..SECTION_SIZE * 6];
+        SectionData::write_footer(torn, 2, Gen3Game::Emerald).unwrap();
+        assert_eq!(
+            get_slot_save_index(&image[..SLOT_SIZE]),
+            Err(Error::MismatchedSaveIndex { section_id: 5, expected: 1, found: 2 })
+        );
+        write_test_slot(&mut image, 0, 1, 0);
+
+        // A duplicated section ID invalidates the slot
+        image[SECTION_SIZE + 0x0FF4] = 0;
+        SectionData::fix_checksum(&mut image[SECTION_SIZE..SECTION_SIZE * 2], Gen3Game::Emerald).unwrap();
+        assert_eq!(get_slot_save_index(&image[..SLOT_SIZE]), Err(Error::InvalidSectionId(0)));
+
+        // A section whose data doesn't match its checksum
+        image[SECTION_SIZE + 0x0010] = 1;
+        assert_eq!(
+            get_slot_save_index(&image[..SLOT_SIZE]),
+            Err(Error::BadChecksum { section_id: 0, stored: 0, computed: 1 })
+        );
+    }
+
+    #[test]
+    fn section_checksums() {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_slot(&mut image, 0, 1, 0);
+
+        let section = &mut image[..SECTION_SIZE];
+        assert!(SectionData::is_valid(section, Gen3Game::RubySapphire));
+
+        // Data past the RS trainer section size is only covered by the other games
+        section[0x0900] = 1;
+        assert!(SectionData::is_valid(section, Gen3Game::RubySapphire));
+        assert!(!SectionData::is_valid(section, Gen3Game::Emerald));
+
+        SectionData::fix_checksum(section, Gen3Game::Emerald).unwrap();
+        assert!(SectionData::is_valid(section, Gen3Game::Emerald));
+        assert_eq!(SectionData::view(section).checksum(), Ok(1));
+
+        // Words are summed as 32bit and folded into 16bit, wrapping on overflow
+        assert_eq!(get_section_checksum(&[0xFF, 0xFF, 0x02, 0x00], 4), Ok(0x0001));
+        assert!(get_section_checksum(&[0xFF, 0xFF], 4).is_err());
+    }
+
+    #[test]
+    fn section_footer_views() {
+        let mut section = vec![0u8; SECTION_SIZE];
+        let mut footer = SectionData::view_mut(&mut section);
+        footer.set_section_id(5).unwrap();
+        footer.set_save_index(0x0102_0304).unwrap();
+        assert_eq!(footer.section_id(), Ok(5));
+
+        assert_eq!(&section[0x0FF4..0x0FF6], [5, 0]);
+        assert_eq!(&section[0x0FFC..], [4, 3, 2, 1]);
+
+        let info = SectionData::new(&section).unwrap();
+        assert_eq!((info.section_id, info.checksum, info.save_index), (5, 0, 0x0102_0304));
+
+        let short = &mut section[..0x0FF8];
+        assert_eq!(SectionData::view(short).signature(), Err(Error::OutOfBounds { offset: 0x0FF8, size: 4, len: 0x0FF8 }));
+        assert!(SectionData::view_mut(short).set_save_index(1).is_err());
+    }
+
+    #[test]
+    fn fix_checksums_after_edit() {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_slot(&mut image, 0, 1, 5);
+
+        let mut save = SaveGen3::from_bytes(&image).unwrap();
+        assert!(save.checksums_valid());
+
+        save.section_mut(3).unwrap()[0x10] = 0x42;
+        assert!(!save.checksums_valid());
+
+        save.fix_checksums().unwrap();
+        assert!(save.checksums_valid());
+    }
+
+    #[test]
+    fn write_back_round_trip() {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_slot(&mut image, 0, 41, 2);
+        write_test_slot(&mut image, 1, 42, 9);
+
+        let mut save = SaveGen3::from_bytes(&image).unwrap();
+        let trainer = save.trainer_section.as_mut().unwrap();
+        trainer.set_name("LENNI").unwrap();
+        trainer.gender = 1;
+        trainer.id.trainer_id = 0x1234_5678;
+        trainer.time.set_hours(300).unwrap();
+        trainer.time.set_minutes(59).unwrap();
+
+        let bytes = save.to_bytes().unwrap();
+        assert_eq!(bytes.len(), SAVE_SIZE);
+        // The save goes into slot A, slot B is kept as the backup of the previous save
+        assert_eq!(bytes[SLOT_SIZE..SLOT_SIZE * 2], image[SLOT_SIZE..SLOT_SIZE * 2]);
+
+        let reloaded = SaveGen3::from_bytes(&bytes).unwrap();
+        assert_eq!(reloaded.active_slot(), 0);
+        assert!(reloaded.checksums_valid());
+
+        // The sections are rotated one further than in slot B
+        assert_eq!(SectionData::view(&bytes[SECTION_SIZE * 10..]).section_id(), Ok(0));
+        assert_eq!(SectionData::view(&bytes[SECTION_SIZE * 9..]).section_id(), Ok(13));
+        assert_eq!(SectionData::view(reloaded.section(0).unwrap()).save_index(), Ok(43));
+
+        let trainer = reloaded.trainer_section.as_ref().unwrap();
+        assert_eq!(trainer.name, [0xC6, 0xBF, 0xC8, 0xC8, 0xC3, 0xFF, 0x00]);
+        assert_eq!(trainer.name(), "LENNI");
+        assert_eq!(trainer.gender, 1);
+        assert_eq!(trainer.id.trainer_id, 0x1234_5678);
+        assert_eq!(trainer.id.tid(), 0x5678);
+        assert_eq!(trainer.id.sid(), 0x1234);
+        assert_eq!(TrainerId::get_tid_from_bytes(reloaded.section(0).unwrap()), Ok(0x5678));
+        assert_eq!(TrainerId::get_sid_from_bytes(reloaded.section(0).unwrap()), Ok(0x1234));
+        assert_eq!(trainer.time.hours(), 300);
+        assert_eq!(trainer.time.minutes(), 59);
+
+        // Without slot A the previous save is loaded from slot B
+        let mut without_slot_a = bytes.clone();
+        without_slot_a[SECTION_SIZE * 10 + 0x0FF8] = 0;
+        let previous = SaveGen3::from_bytes(&without_slot_a).unwrap();
+        assert_eq!(previous.active_slot(), 1);
+        assert_eq!(SectionData::view(previous.section(0).unwrap()).save_index(), Ok(42));
+        assert_eq!(previous.trainer_section.as_ref().unwrap().id.trainer_id, 0);
+
+        // Saving again goes back to slot B, keeping slot A
+        let saved_twice = reloaded.to_bytes().unwrap();
+        assert_eq!(saved_twice[..SLOT_SIZE], bytes[..SLOT_SIZE]);
+        assert_eq!(SectionData::view(&saved_twice[SLOT_SIZE + SECTION_SIZE * 11..]).section_id(), Ok(0));
+
+        let reloaded = SaveGen3::from_bytes(&saved_twice).unwrap();
+        assert_eq!(reloaded.active_slot(), 1);
+        assert!(reloaded.checksums_valid());
+        assert_eq!(SectionData::view(reloaded.section(0).unwrap()).save_index(), Ok(44));
+        assert_eq!(reloaded.trainer_section.as_ref().unwrap().name(), "LENNI");
+    }
+
+    fn test_pk3(species: u16) -> PK3 {
+        let mut pk3 = PK3::from_encrypted_bytes(&[0u8; SIZE_PARTY]).unwrap();
+        pk3.set_species(species);
+        pk3
+    }
+
+    /// Builds a save with the given party, the game is picked by the value at `0xAC`
+    fn test_save_with_party(game: Gen3Game, party: &[PK3]) -> SaveGen3 {
+        let mut image = vec![0u8; SAVE_SIZE];
+        write_test_slot(&mut image, 0, 1, 0);
+
+        if game == Gen3Game::FireRedLeafGreen {
+            image[0x00AC] = 1;
+            SectionData::fix_checksum(&mut image[..SECTION_SIZE], game).unwrap();
+        }
+
+        let team_section = &mut image[SECTION_SIZE..SECTION_SIZE * 2];
+        write_party_to_byte_array(party, team_section, game).unwrap();
+        SectionData::fix_checksum(team_section, game).unwrap();
+
+        SaveGen3::from_bytes(&image).unwrap()
+    }
+
+    #[test]
+    fn party_parsing() {
+        for game in [Gen3Game::RubySapphire, Gen3Game::FireRedLeafGreen] {
+            let save = test_save_with_party(game, &[test_pk3(25), test_pk3(133)]);
+
+            assert_eq!(save.game_ver, game);
+            assert_eq!(save.party().len(), 2);
+            assert_eq!(save.party()[0].species(), 25);
+            assert_eq!(save.party()[1].species(), 133);
+            assert!(save.party().iter().all(|pk3| !pk3.is_bad_egg()));
+        }
+    }
+
+    #[test]
+    fn party_editing() {
+        let mut save = test_save_with_party(Gen3Game::RubySapphire, &[test_pk3(1), test_pk3(4), test_pk3(7)]);
+
+        save.party_mut()[0].set_species(2);
+        save.move_party_member(2, 0).unwrap();
+        assert_eq!(save.move_party_member(3, 0), Err(Error::InvalidIndex { index: 3, count: 3 }));
+        assert_eq!(save.remove_party_member(1).map(|pk3| pk3.species()), Some(2));
+        assert!(save.remove_party_member(5).is_none());
+
+        for species in [10, 11, 12, 13] {
+            assert!(save.add_party_member(test_pk3(species)).is_ok());
+        }
+        assert_eq!(save.add_party_member(test_pk3(14)).map_err(|pk3| pk3.species()), Err(14));
+
+        let stored = PK3::from_encrypted_bytes(&[0u8; 80]).unwrap();
+        save.remove_party_member(5);
+        assert!(save.add_party_member(stored).is_err());
+
+        let reloaded = SaveGen3::from_bytes(&save.to_bytes().unwrap()).unwrap();
+        let species: Vec<u16> = reloaded.party().iter().map(|pk3| pk3.species()).collect();
+        assert_eq!(species, [7, 4, 10, 11, 12]);
+        assert!(reloaded.party().iter().all(|pk3| !pk3.is_bad_egg()));
+    }
+
+    #[test]
+    fn save_charset() {
+        let japanese = |ot_id: u32, egg: bool| {
+            let mut bytes = [0u8; SIZE_PARTY];
+            bytes[0x04..0x08].copy_from_slice(&ot_id.to_le_bytes());
+            bytes[0x12] = 1;
+            // With a PID of 0 the Miscellaneous block stays last and is XORed with the OT ID
+            bytes[0x48..0x4C].copy_from_slice(&(((egg as u32) << 30) ^ ot_id).to_le_bytes());
+            PK3::from_encrypted_bytes(&bytes).unwrap()
+        };
+
+        // Eggs and Pokémon from other trainers don't tell the language of the save
+        let save = test_save_with_party(Gen3Game::Emerald, &[japanese(0, true), japanese(0x1234, false)]);
+        assert_eq!(save.charset(), Gen3Charset::International);
+        assert_eq!(save.trainer_section.as_ref().unwrap().charset, Gen3Charset::International);
+
+        let mut save = test_save_with_party(Gen3Game::Emerald, &[japanese(0, true), japanese(0, false)]);
+        assert_eq!(save.charset(), Gen3Charset::Japanese);
+        assert_eq!(save.pc_storage().unwrap().box_name(0).unwrap(), "　".repeat(9));
+
+        let trainer = save.trainer_section.as_mut().unwrap();
+        trainer.set_name("ゲーフリ").unwrap();
+        assert_eq!(trainer.name(), "ゲーフリ");
+        trainer.charset = Gen3Charset::International;
+        assert!(trainer.set_name("ゲーフリ").is_err());
+    }
+
+    #[test]
+    fn trainer_id_halves() {
+        let mut id = TrainerId::new(0xA1B2_C3D4);
+        assert_eq!((id.tid(), id.sid()), (0xC3D4, 0xA1B2));
+
+        id.set_tid(1);
+        id.set_sid(2);
+        assert_eq!(id, TrainerId::from_parts(1, 2));
+        assert_eq!(id.trainer_id, 0x0002_0001);
+
+        assert_eq!(id.shiny_xor(0x0003_0000), 0);
+        assert!(id.is_shiny(0x0003_0007));
+        assert!(!id.is_shiny(0x0003_0008));
+    }
+
+    #[test]
+    fn played_time() {
+        let mut time = PlayedTime::new(12, 34, 56, 7).unwrap();
+        assert_eq!(time.to_string(), "12:34:56");
+        assert_eq!(time.to_bytes(), [12, 0, 34, 56, 7]);
+        assert_eq!(PlayedTime::from_bytes(&time.to_bytes()), Ok(time));
+
+        assert_eq!(time.set_hours(1000), Err(Error::ValueOutOfRange { value: 1000, max: 999 }));
+        assert!(time.set_minutes(60).is_err());
+        assert!(time.set_seconds(60).is_err());
+        assert!(time.set_frames(60).is_err());
+        assert!(PlayedTime::new(0, 0, 0, 60).is_err());
+        assert_eq!(time, PlayedTime::new(12, 34, 56, 7).unwrap());
+
+        for frames in 0..60 {
+            let time = PlayedTime::new(999, 59, 59, frames).unwrap();
+            assert_eq!(PlayedTime::from_duration(time.to_duration()), time);
+        }
+
+        let duration = time.to_duration();
+        assert_eq!(duration.as_secs(), 12 * 3600 + 34 * 60 + 56);
+        assert_eq!(time.saturating_add(Duration::from_secs(64)), PlayedTime::new(12, 36, 0, 7).unwrap());
+
+        // The game stops counting at 999:59:59
+        assert!(PlayedTime::from_duration(Duration::from_secs(1000 * 3600)).is_max());
+        assert!(PlayedTime::MAX.saturating_add(Duration::from_secs(1)).is_max());
+        assert!(!PlayedTime::new(999, 59, 59, 0).unwrap().is_max());
+        assert_eq!(time.saturating_add(Duration::MAX), PlayedTime::MAX);
+    }
+
+    #[test]
+    fn money_and_coins() {
+        let key: u32 = 0x1357_9BDF;
+
+        for game in Gen3Game::ALL {
+            let mut image = vec![0u8; SAVE_SIZE];
+            write_test_slot(&mut image, 0, 1, 0);
+            match game {
+                Gen3Game::RubySapphire => {}
+                Gen3Game::FireRedLeafGreen => image[0x0AF8..0x0AFC].copy_from_slice(&key.to_le_bytes()),
+                Gen3Game::Emerald => image[0x00AC..0x00B0].copy_from_slice(&key.to_le_bytes()),
+            }
+            SectionData::fix_checksum(&mut image[..SECTION_SIZE], game).unwrap();
+
+            let mut save = SaveGen3::from_bytes_with_game(&image, game).unwrap();
+            save.set_money(123_456).unwrap();
+            save.set_coins(4321).unwrap();
+
+            let stored_key = if game == Gen3Game::RubySapphire { 0 } else { key };
+            let offset = game.money_offset();
+            assert_eq!(save.section(1).unwrap().get_u32_le_offset(offset), Ok(123_456 ^ stored_key));
+            assert_eq!(save.section(1).unwrap().get_u16_le_offset(offset + 4), Ok(4321 ^ stored_key as u16));
+
+            let mut reloaded = SaveGen3::from_bytes_with_game(&save.to_bytes().unwrap(), game).unwrap();
+            assert_eq!(reloaded.money(), Ok(123_456), "{}", game);
+            assert_eq!(reloaded.coins(), Ok(4321), "{}", game);
+
+            reloaded.set_money(u32::MAX).unwrap();
+            reloaded.set_coins(10_000).unwrap();
+            assert_eq!(reloaded.money(), Ok(MAX_MONEY));
+            assert_eq!(reloaded.coins(), Ok(MAX_COINS));
+        }
+    }
+
+    #[test]
+    fn active_slot_wraps_around() {
+        assert_eq!(select_active_slot(Ok(u32::MAX), Ok(0)), Ok(1));
+        assert_eq!(select_active_slot(Ok(0), Ok(u32::MAX)), Ok(0));
+        assert_eq!(select_active_slot(Ok(5), Ok(5)), Ok(0));
+        assert_eq!(select_active_slot(Err(Error::UnknownGame), Ok(5)), Ok(1));
+        assert_eq!(
+            select_active_slot(Err(Error::InvalidSectionId(3)), Err(Error::UnknownGame)),
+            Err(Error::InvalidSectionId(3))
+        );
+    }
 
     #[test]
     fn exploration() {
@@ -169,48 +752,8 @@ mod tests {
                 Err(e) => panic!("{}", e)
             };
         
-        // let save_a_bytes = file_bytes.get_offset(0x0, 57344);
-        let save_b_bytes = file_bytes.get_offset(0x00E000, 57344);
-    
-        // Real world scenario where we can't guarantee that a save file will be passed
-        // let mut data: Option<&[u8]> = None;
-        // for i in 0..14 {
-        //     let offset_for_section_to_be = 4096*i;
-        //     let current_section = save_b_bytes.get_offset(offset_for_section_to_be, 4096);
-        //     let section_id = if let Ok(id) = current_section.get_u16_le_offset(0x0FF4) {
-        //         id
-        //     } else {
-        //         panic!("Could not found section ID at offset: {}", offset_for_section_to_be);
-        //     };
-            
-    
-        //     if section_id == 0 {
-        //         data = Some(current_section);
-        //         break;
-        //     }
-        // }
-
-        // let data = if let Some(inner_data) = data {
-        //     inner_data
-        // } else {
-        //     panic!("The section 0 was not found");
-        // };
-
-        // Because we are in a test, we can guarantee that this save will always have the section 0
-        let mut counter = 0;
-        let data = loop {
-            let current_section = save_b_bytes.get_offset(4096*counter, 4096);
-            let section_id = current_section.get_u16_le_offset(0x0FF4).unwrap();
-    
-            if section_id == 0 {
-                break current_section;
-            }
-
-            counter += 1;
-            if counter > 13 {
-                panic!("We looped through all the sections and the ID 0 was not found");
-            }
-        };
+        let save = SaveGen3::from_bytes(&file_bytes).expect("The test save should have a valid slot");
+        let data = save.section(0).expect("Every valid slot has the section 0");
 
         const A: usize = 0x0FF6;
         byte_struct_test! {
@@ -224,23 +767,23 @@ mod tests {
             save_index: data.get_u32_le_offset(0x0FFC).unwrap(),
         };
         
-        let macro_test = SectionData::new(data);
+        let macro_test = SectionData::new(data).unwrap();
 
-        let proc = TrainerId::get_trainer_id_from_bytes(data);
+        let proc = TrainerId::get_trainer_id_from_bytes(data).unwrap();
         let direct = data.get_u32_le_offset(0x000A).unwrap();
         assert_eq!(proc, direct, "Proc: {}, Direct: {}", proc, direct);
 
-        PlayedTime::get_time_from_bytes(data);
+        PlayedTime::get_time_from_bytes(data).unwrap();
 
         assert_eq!(macro_test.section_id, fn_test.section_id);
         assert_eq!(macro_test.checksum, fn_test.checksum);
         assert_eq!(macro_test.signature, fn_test.signature);
         assert_eq!(macro_test.save_index, fn_test.save_index);
 
-        assert_eq!(SectionData::get_section_id(data), fn_test.section_id);
-        assert_eq!(SectionData::get_checksum(data), fn_test.checksum);
-        assert_eq!(SectionData::get_signature(data), fn_test.signature);
-        assert_eq!(SectionData::get_save_index(data), fn_test.save_index);
+        assert_eq!(SectionData::get_section_id(data), Ok(fn_test.section_id));
+        assert_eq!(SectionData::get_checksum(data), Ok(fn_test.checksum));
+        assert_eq!(SectionData::get_signature(data), Ok(fn_test.signature));
+        assert_eq!(SectionData::get_save_index(data), Ok(fn_test.save_index));
 
     }
 }
\ No newline at end of file
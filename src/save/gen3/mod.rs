@@ -1,2 +1,8 @@
+pub mod gen3_bag;
+pub mod gen3_detect;
+pub mod gen3_events;
+pub mod gen3_pc;
+pub mod gen3_pokedex;
 pub mod gen3_save;
+pub mod gen3_text;
 pub mod gen3_utils;
\ No newline at end of file
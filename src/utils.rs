@@ -1,13 +1,33 @@
-use std::array::TryFromSliceError;
+use crate::error::Error;
 
 macro_rules! impl_sliceutils {
     () => {
-        fn write_into(&mut self, data: &[u8], offset: usize) {
-            self[offset..(data.len())].copy_from_slice(data);
+        /// Copies the data into the bytes starting on the offset,
+        /// failing without writing anything if it doesn't fit
+        ///
+        /// # Examples
+        /// ```
+        /// # use pkhex_rs::utils::SliceUtils;
+        /// # let mut bytes = [0u8; 4];
+        ///
+        /// bytes.write_into(&[0x2A, 0x2B], 0x2)?;
+        ///
+        /// # assert_eq!(bytes, [0, 0, 0x2A, 0x2B]);
+        /// # assert!(bytes.write_into(&[0x2A, 0x2B], 0x3).is_err());
+        /// # Ok::<(), pkhex_rs::error::Error>(())
+        /// ```
+        fn write_into(&mut self, data: &[u8], offset: usize) -> Result<(), Error> {
+            self.get_mutable_offset(offset, data.len())?.copy_from_slice(data);
+            Ok(())
         }
 
-        fn get_mutable_slice(&mut self, start: usize, end: usize) -> &mut [u8] {
-            &mut self[start..=end]
+        fn get_mutable_slice(&mut self, start: usize, end: usize) -> Result<&mut [u8], Error> {
+            let len = self.len();
+            self.get_mut(start..=end).ok_or(Error::OutOfBounds {
+                offset: start,
+                size: end.saturating_sub(start).saturating_add(1),
+                len,
+            })
         }
 
         /// Returns a refence to a slice of the original array
@@ -18,24 +38,43 @@ macro_rules! impl_sliceutils {
         /// # use pkhex_rs::utils::SliceUtils;
         /// # let bytes = [0x2A, 0, 0, 0];
         ///
-        /// let offset = bytes.get_offset(0x0, 1);
+        /// let offset = bytes.get_offset(0x0, 1)?;
         ///
         /// # assert_eq!(&[42], offset);
+        /// # assert!(bytes.get_offset(0x2, 4).is_err());
+        /// # Ok::<(), pkhex_rs::error::Error>(())
         /// ```
-        fn get_offset(&self, offset: usize, byte_quantity: usize) -> &[u8] {
-            &self[offset..offset + byte_quantity]
+        fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error> {
+            offset
+                .checked_add(byte_quantity)
+                .and_then(|end| self.get(offset..end))
+                .ok_or(Error::OutOfBounds {
+                    offset,
+                    size: byte_quantity,
+                    len: self.len(),
+                })
         }
 
-        fn get_mutable_offset(&mut self, offset: usize, byte_quantity: usize) -> &mut [u8] {
-            self.get_mutable_slice(offset, offset + byte_quantity - 1)
+        fn get_mutable_offset(&mut self, offset: usize, byte_quantity: usize) -> Result<&mut [u8], Error> {
+            let len = self.len();
+            offset
+                .checked_add(byte_quantity)
+                .and_then(|end| self.get_mut(offset..end))
+                .ok_or(Error::OutOfBounds {
+                    offset,
+                    size: byte_quantity,
+                    len,
+                })
         }
 
-        fn get_u16_le(&self) -> Result<u16, TryFromSliceError> {
-            Ok(u16::from_le_bytes(self[..2].try_into()?))
+        fn get_u16_le(&self) -> Result<u16, Error> {
+            let bytes = self.get_offset(0, std::mem::size_of::<u16>())?;
+            Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
         }
 
-        fn get_u32_le(&self) -> Result<u32, TryFromSliceError> {
-            Ok(u32::from_le_bytes(self[..4].try_into()?))
+        fn get_u32_le(&self) -> Result<u32, Error> {
+            let bytes = self.get_offset(0, std::mem::size_of::<u32>())?;
+            Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
         }
 
         /// Returns an unsigned 16bit little-endian integer
@@ -43,17 +82,17 @@ macro_rules! impl_sliceutils {
         ///
         /// # Examples
         /// ```
-        /// # use std::array::TryFromSliceError;
+        /// # use pkhex_rs::error::Error;
         /// # use pkhex_rs::utils::SliceUtils;
         /// # let bytes = [ 0x2A, 0, 0, 0 ];
         ///
         /// let number = bytes.get_u16_le_offset(0x0)?;
         ///
         /// # assert_eq!(42, number);
-        /// # Ok::<(), TryFromSliceError>(())
+        /// # Ok::<(), Error>(())
         /// ```
-        fn get_u16_le_offset(&self, offset: usize) -> Result<u16, TryFromSliceError> {
-            self.get_offset(offset, std::mem::size_of::<u16>())
+        fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error> {
+            self.get_offset(offset, std::mem::size_of::<u16>())?
                 .get_u16_le()
         }
 
@@ -62,22 +101,42 @@ macro_rules! impl_sliceutils {
         ///
         /// # Examples
         /// ```
-        /// # use std::array::TryFromSliceError;
+        /// # use pkhex_rs::error::Error;
         /// # use pkhex_rs::utils::SliceUtils;
         /// # let bytes = [ 0x2A, 0, 0, 0 ];
         ///
         /// let number = bytes.get_u32_le_offset(0x0)?;
         ///
         /// # assert_eq!(42, number);
-        /// # Ok::<(), TryFromSliceError>(())
+        /// # Ok::<(), Error>(())
         /// ```
-        fn get_u32_le_offset(&self, offset: usize) -> Result<u32, TryFromSliceError> {
-            self.get_offset(offset, std::mem::size_of::<u32>())
+        fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error> {
+            self.get_offset(offset, std::mem::size_of::<u32>())?
                 .get_u32_le()
         }
     };
 }
 
+/// Declares the getters and setters of integers in the given endianess,
+/// reading and writing through [`SliceUtils::get_array_offset`] and [`SliceUtils::set_array_offset`]
+macro_rules! endian_accessors {
+    ($endianess:ident => get: $($get_type:ty),* ; set: $($set_type:ty),*) => {
+        paste::paste! {
+            $(
+                fn [<get_ $get_type _ $endianess _offset>](&self, offset: usize) -> Result<$get_type, Error> {
+                    Ok($get_type::[<from_ $endianess _bytes>](self.get_array_offset(offset)?))
+                }
+            )*
+
+            $(
+                fn [<set_ $set_type _ $endianess _offset>](&mut self, offset: usize, value: $set_type) -> Result<(), Error> {
+                    self.set_array_offset(offset, value.[<to_ $endianess _bytes>]())
+                }
+            )*
+        }
+    };
+}
+
 macro_rules! impl_sliceutils_for {
     ($($type:ty),+) => {
         $(
@@ -88,18 +147,84 @@ macro_rules! impl_sliceutils_for {
     };
 }
 
+/// Bounds-checked reads and writes of bytes and integers in either endianess,
+/// little-endian for the GBA games and big-endian for the GameCube ones
+///
+/// Every integer from `u8` to `i64` has a `get_<TYPE>_<ENDIANESS>_offset` and a
+/// `set_<TYPE>_<ENDIANESS>_offset` method, except the single bytes which have no endianess
 pub trait SliceUtils {
-    fn write_into(&mut self, data: &[u8], offset: usize);
+    fn write_into(&mut self, data: &[u8], offset: usize) -> Result<(), Error>;
+
+    fn get_mutable_slice(&mut self, start: usize, end: usize) -> Result<&mut [u8], Error>;
+    fn get_offset(&self, offset: usize, byte_quantity: usize) -> Result<&[u8], Error>;
+    fn get_mutable_offset(&mut self, offset: usize, byte_quantity: usize) -> Result<&mut [u8], Error>;
+
+    fn get_u16_le(&self) -> Result<u16, Error>;
+    fn get_u32_le(&self) -> Result<u32, Error>;
+
+    fn get_u16_be(&self) -> Result<u16, Error> {
+        self.get_u16_be_offset(0)
+    }
+
+    fn get_u32_be(&self) -> Result<u32, Error> {
+        self.get_u32_be_offset(0)
+    }
+
+    fn get_u16_le_offset(&self, offset: usize) -> Result<u16, Error>;
+    fn get_u32_le_offset(&self, offset: usize) -> Result<u32, Error>;
+
+    /// Copies `N` bytes starting on the offset into an array
+    ///
+    /// # Examples
+    /// ```
+    /// # use pkhex_rs::error::Error;
+    /// # use pkhex_rs::utils::SliceUtils;
+    /// # let bytes = [ 0x2A, 0x2B, 0x2C, 0 ];
+    ///
+    /// let array: [u8; 2] = bytes.get_array_offset(0x1)?;
+    ///
+    /// # assert_eq!(array, [0x2B, 0x2C]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    fn get_array_offset<const N: usize>(&self, offset: usize) -> Result<[u8; N], Error> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.get_offset(offset, N)?);
+        Ok(array)
+    }
+
+    fn set_array_offset<const N: usize>(&mut self, offset: usize, value: [u8; N]) -> Result<(), Error> {
+        self.get_mutable_offset(offset, N)?.copy_from_slice(&value);
+        Ok(())
+    }
+
+    fn get_u8_offset(&self, offset: usize) -> Result<u8, Error> {
+        Ok(self.get_offset(offset, 1)?[0])
+    }
+
+    fn set_u8_offset(&mut self, offset: usize, value: u8) -> Result<(), Error> {
+        self.get_mutable_offset(offset, 1)?[0] = value;
+        Ok(())
+    }
+
+    fn get_i8_offset(&self, offset: usize) -> Result<i8, Error> {
+        Ok(self.get_u8_offset(offset)? as i8)
+    }
 
-    fn get_mutable_slice(&mut self, start: usize, end: usize) -> &mut [u8];
-    fn get_offset(&self, offset: usize, byte_quantity: usize) -> &[u8];
-    fn get_mutable_offset(&mut self, offset: usize, byte_quantity: usize) -> &mut [u8];
+    fn set_i8_offset(&mut self, offset: usize, value: i8) -> Result<(), Error> {
+        self.set_u8_offset(offset, value as u8)
+    }
 
-    fn get_u16_le(&self) -> Result<u16, TryFromSliceError>;
-    fn get_u32_le(&self) -> Result<u32, TryFromSliceError>;
+    /// Any byte other than `0` is read as `true`
+    fn get_bool_offset(&self, offset: usize) -> Result<bool, Error> {
+        Ok(self.get_u8_offset(offset)? != 0)
+    }
 
-    fn get_u16_le_offset(&self, offset: usize) -> Result<u16, TryFromSliceError>;
-    fn get_u32_le_offset(&self, offset: usize) -> Result<u32, TryFromSliceError>;
+    fn set_bool_offset(&mut self, offset: usize, value: bool) -> Result<(), Error> {
+        self.set_u8_offset(offset, value as u8)
+    }
+
+    endian_accessors! { le => get: i16, i32, u64, i64; set: u16, i16, u32, i32, u64, i64 }
+    endian_accessors! { be => get: u16, i16, u32, i32, u64, i64; set: u16, i16, u32, i32, u64, i64 }
 }
 
 impl_sliceutils_for! { [u8], Vec<u8> }
@@ -110,19 +235,101 @@ impl<const K: usize> SliceUtils for [u8; K] {
 
 #[cfg(test)]
 mod tests {
+    use crate::error::Error;
+
     use super::SliceUtils;
 
     #[test]
     fn exploration() {
         let mut bytes = vec![0u8, 0u8, 0u8, 0u8];
 
-        let slice = bytes.get_mutable_offset(1, 2);
+        let slice = bytes.get_mutable_offset(1, 2).unwrap();
 
         let data = u16::to_le_bytes(0x2);
-        slice.write_into(&data, 0);
+        slice.write_into(&data, 0).unwrap();
 
         assert_eq!(bytes, [0u8, 2u8, 0u8, 0u8]);
         assert_eq!(u16::to_ne_bytes(0x2), u16::to_le_bytes(0x2));
         assert_ne!(u16::to_be_bytes(0x2), u16::to_le_bytes(0x2));
     }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut bytes = [0u8; 4];
+
+        assert_eq!(bytes.get_u32_le_offset(1), Err(Error::OutOfBounds { offset: 1, size: 4, len: 4 }));
+        assert_eq!(bytes[..1].get_u16_le(), Err(Error::OutOfBounds { offset: 0, size: 2, len: 1 }));
+        assert_eq!(bytes.get_offset(usize::MAX, 2), Err(Error::OutOfBounds { offset: usize::MAX, size: 2, len: 4 }));
+        assert!(bytes.get_mutable_offset(3, 2).is_err());
+        assert!(bytes.get_mutable_slice(2, 3).is_ok());
+    }
+
+    #[test]
+    fn both_endianess() {
+        let mut bytes = vec![0u8; 9];
+
+        bytes.set_u16_le_offset(1, 0x0102).unwrap();
+        assert_eq!(bytes[1..3], [0x02, 0x01]);
+        assert_eq!(bytes.get_u16_be_offset(1), Ok(0x0201));
+        bytes.set_i16_be_offset(1, -2).unwrap();
+        assert_eq!(bytes[1..3], [0xFF, 0xFE]);
+        assert_eq!(bytes.get_i16_le_offset(1), Ok(-257));
+
+        bytes.set_u32_be_offset(1, 0x0102_0304).unwrap();
+        assert_eq!(bytes.get_u32_le_offset(1), Ok(0x0403_0201));
+        assert_eq!(bytes[1..].get_u32_be(), Ok(0x0102_0304));
+        bytes.set_i32_be_offset(1, i32::MIN).unwrap();
+        assert_eq!(bytes.get_i32_le_offset(1), Ok(0x80));
+
+        bytes.set_i64_le_offset(1, -2).unwrap();
+        assert_eq!(bytes.get_i64_le_offset(1), Ok(-2));
+        assert_eq!(bytes.get_i64_be_offset(1), Ok(-0x0100_0000_0000_0001));
+        bytes.set_u64_be_offset(1, 0x0102_0304_0506_0708).unwrap();
+        assert_eq!(bytes, [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(bytes.get_u16_be(), Ok(0x0001));
+
+        assert_eq!(bytes.get_i64_be_offset(2), Err(Error::OutOfBounds { offset: 2, size: 8, len: 9 }));
+        assert!(bytes.set_i64_le_offset(2, 0).is_err());
+        assert_eq!(bytes[8], 8);
+    }
+
+    #[test]
+    fn write_into_offset() {
+        let mut bytes = [0u8; 6];
+
+        bytes.write_into(&[1, 2, 3], 2).unwrap();
+        assert_eq!(bytes, [0, 0, 1, 2, 3, 0]);
+        assert_eq!(bytes.write_into(&[4, 5], 5), Err(Error::OutOfBounds { offset: 5, size: 2, len: 6 }));
+        assert_eq!(bytes[5], 0);
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let mut bytes = [0u8; 10];
+
+        bytes.set_u16_be_offset(0, 0x1234).unwrap();
+        bytes.set_i32_le_offset(2, -2).unwrap();
+        bytes.set_bool_offset(6, true).unwrap();
+        bytes.set_i8_offset(7, -1).unwrap();
+        bytes.set_array_offset(8, [0xAB, 0xCD]).unwrap();
+        assert_eq!(bytes, [0x12, 0x34, 0xFE, 0xFF, 0xFF, 0xFF, 0x01, 0xFF, 0xAB, 0xCD]);
+
+        assert_eq!(bytes.get_u16_be_offset(0), Ok(0x1234));
+        assert_eq!(bytes.get_u16_le_offset(0), Ok(0x3412));
+        assert_eq!(bytes.get_i32_le_offset(2), Ok(-2));
+        assert_eq!(bytes.get_i16_be_offset(2), Ok(-257));
+        assert_eq!(bytes.get_u8_offset(7), Ok(0xFF));
+        assert_eq!(bytes.get_i8_offset(7), Ok(-1));
+        assert_eq!(bytes.get_bool_offset(6), Ok(true));
+        assert_eq!(bytes.get_array_offset::<3>(7), Ok([0xFF, 0xAB, 0xCD]));
+
+        bytes.set_u64_le_offset(2, u64::MAX).unwrap();
+        assert_eq!(bytes.get_u64_be_offset(2), Ok(u64::MAX));
+        assert_eq!(bytes.get_u32_be_offset(0), Ok(0x1234_FFFF));
+
+        assert_eq!(bytes.get_u64_le_offset(3), Err(Error::OutOfBounds { offset: 3, size: 8, len: 10 }));
+        assert!(bytes.set_u16_be_offset(9, 0).is_err());
+        assert!(bytes.set_array_offset(9, [0, 0]).is_err());
+        assert_eq!(bytes[9], 0xFF);
+    }
 }